@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+
+use crate::error::*;
+use super::{ check_discriminator, read_pubkey, PoolInfo };
+
+pub const ID: Pubkey = pubkey!("Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB");
+pub const DEX_NAME: &str = "meteora amm";
+
+const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+//Pool: lp_mint, token_a_mint, token_b_mint
+const LP_MINT_OFFSET: usize = 8;
+const MINT_A_OFFSET: usize = 40;
+const MINT_B_OFFSET: usize = 72;
+
+pub fn read_pool(data: &[u8]) -> Result<PoolInfo> {
+    if !check_discriminator(data, &POOL_DISCRIMINATOR) {
+        return Err(LockError::InvalidPoolAccount.into());
+    }
+    Ok(PoolInfo {
+        lp_mint: read_pubkey(data, LP_MINT_OFFSET)?,
+        token_mint_a: read_pubkey(data, MINT_A_OFFSET)?,
+        token_mint_b: read_pubkey(data, MINT_B_OFFSET)?,
+    })
+}
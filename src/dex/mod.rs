@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+
+use crate::error::*;
+use crate::state::LpLock;
+
+pub mod meteora_amm;
+pub mod orca_whirlpool;
+pub mod raydium_amm;
+pub mod raydium_clmm;
+pub mod raydium_cpmm;
+
+//what a lock needs from a clmm position account
+pub struct PositionInfo {
+    pub pool: Pubkey,
+    pub position_mint: Pubkey,
+}
+
+//what a lock needs from an amm pool account
+pub struct PoolInfo {
+    pub lp_mint: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+}
+
+//a pool the lp mint was verified against
+pub struct VerifiedPool {
+    pub dex_name: &'static str,
+    pub dex_program: Pubkey,
+    pub pool: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub position: Option<Pubkey>, //set for clmm positions
+}
+
+impl VerifiedPool {
+    //marks the lock verified, clmm positions can also collect fees afterwards
+    pub fn record(&self, lp_lock: &mut LpLock) {
+        lp_lock.pool = self.pool;
+        lp_lock.verified = true;
+        if let Some(position) = self.position {
+            lp_lock.dex_program = self.dex_program;
+            lp_lock.position = position;
+        }
+    }
+}
+
+pub fn dex_name(dex_program: &Pubkey) -> Result<&'static str> {
+    match *dex_program {
+        orca_whirlpool::ID => Ok(orca_whirlpool::DEX_NAME),
+        raydium_clmm::ID => Ok(raydium_clmm::DEX_NAME),
+        _ => Err(LockError::UnsupportedDex.into()),
+    }
+}
+
+//checks the position is owned by the dex program and reads it
+pub fn read_position(dex_program: &Pubkey, position: &AccountInfo) -> Result<PositionInfo> {
+    if position.owner != dex_program {
+        return Err(LockError::InvalidPositionAccount.into());
+    }
+    match *dex_program {
+        orca_whirlpool::ID => orca_whirlpool::read_position(&position.try_borrow_data()?),
+        raydium_clmm::ID => raydium_clmm::read_position(&position.try_borrow_data()?),
+        _ => Err(LockError::UnsupportedDex.into()),
+    }
+}
+
+//checks the pool is owned by the dex program and returns its two mints
+pub fn read_pool_mints(dex_program: &Pubkey, pool: &AccountInfo) -> Result<(Pubkey, Pubkey)> {
+    if pool.owner != dex_program {
+        return Err(LockError::InvalidPoolAccount.into());
+    }
+    match *dex_program {
+        orca_whirlpool::ID => orca_whirlpool::read_pool_mints(&pool.try_borrow_data()?),
+        raydium_clmm::ID => raydium_clmm::read_pool_mints(&pool.try_borrow_data()?),
+        _ => Err(LockError::UnsupportedDex.into()),
+    }
+}
+
+//instruction data that only collects fees or rewards, the position liquidity never moves
+pub fn collect_data(dex_program: &Pubkey, reward_index: Option<u8>) -> Result<Vec<u8>> {
+    match *dex_program {
+        orca_whirlpool::ID => Ok(orca_whirlpool::collect_data(reward_index)),
+        raydium_clmm::ID => Ok(raydium_clmm::collect_data()),
+        _ => Err(LockError::UnsupportedDex.into()),
+    }
+}
+
+//checks the lock accounts sit where the dex expects the position owner, position and pool
+pub fn check_collect_accounts(
+    dex_program: &Pubkey,
+    accounts: &[AccountInfo],
+    lock_pda: &Pubkey,
+    lock_vault: &Pubkey,
+    position: &Pubkey,
+    pool: &Pubkey
+) -> Result<()> {
+    let (owner_index, vault_index, position_index, pool_index) = match *dex_program {
+        orca_whirlpool::ID => orca_whirlpool::COLLECT_ACCOUNT_INDEXES,
+        raydium_clmm::ID => raydium_clmm::COLLECT_ACCOUNT_INDEXES,
+        _ => {
+            return Err(LockError::UnsupportedDex.into());
+        }
+    };
+    let key_at = |index: usize| accounts.get(index).map(|a| a.key());
+    if
+        key_at(owner_index) != Some(*lock_pda) ||
+        key_at(vault_index) != Some(*lock_vault) ||
+        key_at(position_index) != Some(*position) ||
+        key_at(pool_index) != Some(*pool)
+    {
+        return Err(LockError::InvalidPositionAccount.into());
+    }
+    Ok(())
+}
+
+//checks the lp mint belongs to the pool, the dex is picked by the pool owner.
+//clmm pools have no lp mint, the position nft is checked through its position account instead.
+pub fn verify_lp_mint(pool: &AccountInfo, position: Option<&AccountInfo>, lp_mint: &Pubkey) -> Result<VerifiedPool> {
+    let amm_pool = match *pool.owner {
+        raydium_amm::ID => Some((raydium_amm::DEX_NAME, raydium_amm::read_pool(&pool.try_borrow_data()?)?)),
+        raydium_cpmm::ID => Some((raydium_cpmm::DEX_NAME, raydium_cpmm::read_pool(&pool.try_borrow_data()?)?)),
+        meteora_amm::ID => Some((meteora_amm::DEX_NAME, meteora_amm::read_pool(&pool.try_borrow_data()?)?)),
+        orca_whirlpool::ID | raydium_clmm::ID => None,
+        _ => {
+            return Err(LockError::UnsupportedDex.into());
+        }
+    };
+
+    if let Some((dex_name, pool_info)) = amm_pool {
+        require_keys_eq!(pool_info.lp_mint, *lp_mint, LockError::LpMintMismatch);
+        return Ok(VerifiedPool {
+            dex_name,
+            dex_program: *pool.owner,
+            pool: pool.key(),
+            token_mint_a: pool_info.token_mint_a,
+            token_mint_b: pool_info.token_mint_b,
+            position: None,
+        });
+    }
+
+    let dex_program = *pool.owner;
+    let position = position.ok_or(LockError::InvalidPositionAccount)?;
+    let position_info = read_position(&dex_program, position)?;
+    require_keys_eq!(position_info.position_mint, *lp_mint, LockError::LpMintMismatch);
+    require_keys_eq!(position_info.pool, pool.key(), LockError::InvalidPoolAccount);
+    let (token_mint_a, token_mint_b) = read_pool_mints(&dex_program, pool)?;
+
+    Ok(VerifiedPool {
+        dex_name: dex_name(&dex_program)?,
+        dex_program,
+        pool: pool.key(),
+        token_mint_a,
+        token_mint_b,
+        position: Some(position.key()),
+    })
+}
+
+pub(crate) fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey> {
+    let bytes = data.get(offset..offset + 32).ok_or(LockError::InvalidPositionAccount)?;
+    Ok(Pubkey::try_from(bytes).unwrap())
+}
+
+pub(crate) fn check_discriminator(data: &[u8], discriminator: &[u8; 8]) -> bool {
+    data.len() >= 8 && data[..8] == discriminator[..]
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+
+use crate::error::*;
+use super::{ check_discriminator, read_pubkey, PositionInfo };
+
+pub const ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+pub const DEX_NAME: &str = "orca whirlpool";
+
+const POSITION_DISCRIMINATOR: [u8; 8] = [170, 188, 143, 228, 122, 64, 247, 208];
+const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+const COLLECT_FEES_DISCRIMINATOR: [u8; 8] = [164, 152, 207, 99, 30, 186, 19, 182];
+const COLLECT_REWARD_DISCRIMINATOR: [u8; 8] = [70, 5, 132, 87, 86, 235, 177, 34];
+
+//Position: whirlpool, position_mint
+const POSITION_WHIRLPOOL_OFFSET: usize = 8;
+const POSITION_MINT_OFFSET: usize = 40;
+//Whirlpool: token_mint_a, token_mint_b
+const WHIRLPOOL_MINT_A_OFFSET: usize = 101;
+const WHIRLPOOL_MINT_B_OFFSET: usize = 181;
+
+//collect_fees and collect_reward: whirlpool, position_authority, position, position_token_account
+//(position_authority, position_token_account, position, whirlpool)
+pub const COLLECT_ACCOUNT_INDEXES: (usize, usize, usize, usize) = (1, 3, 2, 0);
+
+pub fn read_position(data: &[u8]) -> Result<PositionInfo> {
+    if !check_discriminator(data, &POSITION_DISCRIMINATOR) {
+        return Err(LockError::InvalidPositionAccount.into());
+    }
+    Ok(PositionInfo {
+        pool: read_pubkey(data, POSITION_WHIRLPOOL_OFFSET)?,
+        position_mint: read_pubkey(data, POSITION_MINT_OFFSET)?,
+    })
+}
+
+pub fn read_pool_mints(data: &[u8]) -> Result<(Pubkey, Pubkey)> {
+    if !check_discriminator(data, &WHIRLPOOL_DISCRIMINATOR) {
+        return Err(LockError::InvalidPoolAccount.into());
+    }
+    Ok((read_pubkey(data, WHIRLPOOL_MINT_A_OFFSET)?, read_pubkey(data, WHIRLPOOL_MINT_B_OFFSET)?))
+}
+
+//fees must be brought up to date with the permissionless update_fees_and_rewards first
+pub fn collect_data(reward_index: Option<u8>) -> Vec<u8> {
+    match reward_index {
+        None => COLLECT_FEES_DISCRIMINATOR.to_vec(),
+        Some(index) => {
+            let mut data = COLLECT_REWARD_DISCRIMINATOR.to_vec();
+            data.push(index);
+            data
+        }
+    }
+}
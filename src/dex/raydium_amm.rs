@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+
+use crate::error::*;
+use super::{ read_pubkey, PoolInfo };
+
+pub const ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+pub const DEX_NAME: &str = "raydium amm v4";
+
+//AmmInfo has no discriminator, it is the only account of this size the program owns
+const AMM_INFO_LEN: usize = 752;
+
+//AmmInfo: coin_vault_mint, pc_vault_mint, lp_mint
+const COIN_MINT_OFFSET: usize = 400;
+const PC_MINT_OFFSET: usize = 432;
+const LP_MINT_OFFSET: usize = 464;
+
+pub fn read_pool(data: &[u8]) -> Result<PoolInfo> {
+    if data.len() != AMM_INFO_LEN {
+        return Err(LockError::InvalidPoolAccount.into());
+    }
+    Ok(PoolInfo {
+        lp_mint: read_pubkey(data, LP_MINT_OFFSET)?,
+        token_mint_a: read_pubkey(data, COIN_MINT_OFFSET)?,
+        token_mint_b: read_pubkey(data, PC_MINT_OFFSET)?,
+    })
+}
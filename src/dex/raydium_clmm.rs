@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+
+use crate::error::*;
+use super::{ check_discriminator, read_pubkey, PositionInfo };
+
+pub const ID: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+pub const DEX_NAME: &str = "raydium clmm";
+
+const PERSONAL_POSITION_DISCRIMINATOR: [u8; 8] = [70, 111, 150, 126, 230, 15, 25, 117];
+const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+const DECREASE_LIQUIDITY_V2_DISCRIMINATOR: [u8; 8] = [58, 127, 188, 62, 79, 82, 196, 96];
+
+//PersonalPositionState: nft_mint, pool_id
+const POSITION_MINT_OFFSET: usize = 9;
+const POSITION_POOL_OFFSET: usize = 41;
+//PoolState: token_mint_0, token_mint_1
+const POOL_MINT_0_OFFSET: usize = 73;
+const POOL_MINT_1_OFFSET: usize = 105;
+
+//decrease_liquidity_v2: nft_owner, nft_account, personal_position, pool_state
+//(nft_owner, nft_account, personal_position, pool_state)
+pub const COLLECT_ACCOUNT_INDEXES: (usize, usize, usize, usize) = (0, 1, 2, 3);
+
+pub fn read_position(data: &[u8]) -> Result<PositionInfo> {
+    if !check_discriminator(data, &PERSONAL_POSITION_DISCRIMINATOR) {
+        return Err(LockError::InvalidPositionAccount.into());
+    }
+    Ok(PositionInfo {
+        pool: read_pubkey(data, POSITION_POOL_OFFSET)?,
+        position_mint: read_pubkey(data, POSITION_MINT_OFFSET)?,
+    })
+}
+
+pub fn read_pool_mints(data: &[u8]) -> Result<(Pubkey, Pubkey)> {
+    if !check_discriminator(data, &POOL_STATE_DISCRIMINATOR) {
+        return Err(LockError::InvalidPoolAccount.into());
+    }
+    Ok((read_pubkey(data, POOL_MINT_0_OFFSET)?, read_pubkey(data, POOL_MINT_1_OFFSET)?))
+}
+
+//decreasing zero liquidity collects the fees, and the rewards passed after the fixed accounts
+pub fn collect_data() -> Vec<u8> {
+    let mut data = DECREASE_LIQUIDITY_V2_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&0u128.to_le_bytes()); //liquidity
+    data.extend_from_slice(&0u64.to_le_bytes()); //amount_0_min
+    data.extend_from_slice(&0u64.to_le_bytes()); //amount_1_min
+    data
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+
+use crate::error::*;
+use super::{ check_discriminator, read_pubkey, PoolInfo };
+
+pub const ID: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+pub const DEX_NAME: &str = "raydium cpmm";
+
+const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+
+//PoolState: lp_mint, token_0_mint, token_1_mint
+const LP_MINT_OFFSET: usize = 136;
+const MINT_0_OFFSET: usize = 168;
+const MINT_1_OFFSET: usize = 200;
+
+pub fn read_pool(data: &[u8]) -> Result<PoolInfo> {
+    if !check_discriminator(data, &POOL_STATE_DISCRIMINATOR) {
+        return Err(LockError::InvalidPoolAccount.into());
+    }
+    Ok(PoolInfo {
+        lp_mint: read_pubkey(data, LP_MINT_OFFSET)?,
+        token_mint_a: read_pubkey(data, MINT_0_OFFSET)?,
+        token_mint_b: read_pubkey(data, MINT_1_OFFSET)?,
+    })
+}
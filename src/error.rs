@@ -27,5 +27,73 @@ pub enum LockError {
     #[msg("AlreadyDidFirstClaim")]
     AlreadyDidFirstClaim,
     #[msg("Not Bigger Than End Time")]
-    NotBiggerThanEndTime
+    NotBiggerThanEndTime,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Lock duration is longer than allowed")]
+    LockTooLong,
+    #[msg("Too many vesting users")]
+    TooManyBeneficiaries,
+    #[msg("Config value is not valid")]
+    InvalidConfig,
+    #[msg("Treasury account is not valid")]
+    InvalidTreasury,
+    #[msg("Not a token lock")]
+    NotTokenLock,
+    #[msg("Vesting schedule is not valid")]
+    InvalidVestingSchedule,
+    #[msg("Lock still holds tokens")]
+    LockNotEmpty,
+    #[msg("Vesting beneficiary account is not valid")]
+    InvalidBeneficiaryAccount,
+    #[msg("Exactly one lock account must be passed")]
+    InvalidLockAccount,
+    #[msg("Vesting pool is already finalized")]
+    VestingPoolFinalized,
+    #[msg("Vesting pool is not finalized")]
+    VestingPoolNotFinalized,
+    #[msg("Merkle proof is not valid")]
+    InvalidMerkleProof,
+    #[msg("Vesting pool already has beneficiaries or a merkle root")]
+    MerkleRootAlreadySet,
+    #[msg("Vesting pool has no merkle root")]
+    NotMerkleVesting,
+    #[msg("User list and amount list lengths differ")]
+    BeneficiaryLengthMismatch,
+    #[msg("Beneficiary is listed more than once")]
+    DuplicateBeneficiary,
+    #[msg("Beneficiary amount is zero")]
+    BeneficiaryAmountZero,
+    #[msg("TGE release is more than 100%")]
+    InvalidTgeBps,
+    #[msg("Cycle release must be above 0 and at most 100%")]
+    InvalidCycleBps,
+    #[msg("Vesting period is not valid")]
+    InvalidVestingPeriod,
+    #[msg("Mint has an unsupported token extension")]
+    UnsupportedMint,
+    #[msg("Mint extension lets the issuer freeze or strand the locked tokens")]
+    UnsafeMintExtension,
+    #[msg("Dex program is not supported")]
+    UnsupportedDex,
+    #[msg("Position account is not valid")]
+    InvalidPositionAccount,
+    #[msg("Pool account is not valid")]
+    InvalidPoolAccount,
+    #[msg("Lock does not hold a dex position")]
+    NotDexPosition,
+    #[msg("LP mint does not belong to the pool")]
+    LpMintMismatch,
+    #[msg("Vesting has already started")]
+    VestingStarted,
+    #[msg("Lock id account is not valid")]
+    InvalidLockIdAccount,
+    #[msg("Lock index account is not valid")]
+    InvalidLockIndexAccount,
+    #[msg("Treasury token account is required to pay the token fee")]
+    MissingTreasuryTokenAccount,
+    #[msg("Vault holds withheld transfer fees, pass the vault mint to harvest them")]
+    WithheldTransferFees,
 }
@@ -1,11 +1,15 @@
 use anchor_lang::prelude::*;
 
+use crate::state::*;
+
+//`transfer_fee` is the amount withheld by the token-2022 transfer fee extension, 0 for other mints
+
 #[event]
 pub struct CreateLockEvent {
     pub event_name: String,
     pub seed: String,
     pub lock_pda: Pubkey,
-    pub lock_id: u32,
+    pub lock_id: u64,
     pub authority: Pubkey,
     pub spl_mint: Pubkey,
     pub spl_mint_metadata_pda: Pubkey,
@@ -14,10 +18,13 @@ pub struct CreateLockEvent {
     pub end_time: u64,
     pub lock_name: String,
     pub extra_data: String,
-    pub tge_bps: u8,
+    pub tge_bps: u16,
     pub cycle: u64,
-    pub cycle_bps: u8,
+    pub cycle_bps: u16,
     pub dex_name: String,
+    pub fee_lamports: u64,
+    pub fee_amount: u64,
+    pub transfer_fee: u64,
 }
 
 #[event]
@@ -25,18 +32,20 @@ pub struct UnlockEvent {
     pub event_name: String,
     pub seed: String,
     pub lock_pda: Pubkey,
-    pub lock_id: u32,
+    pub lock_id: u64,
     pub authority: Pubkey,
     pub spl_mint: Pubkey,
     pub spl_mint_metadata_pda: Pubkey,
     pub unlock_amount: u64,
+    pub remaining_amount: u64,
     pub start_time: u64,
     pub end_time: u64,
     pub lock_name: String,
     pub extra_data: String,
-    pub tge_bps: u8,
+    pub tge_bps: u16,
     pub cycle: u64,
-    pub cycle_bps: u8,
+    pub cycle_bps: u16,
+    pub transfer_fee: u64,
 }
 
 #[event]
@@ -44,7 +53,7 @@ pub struct CreateDexLockEvent {
     pub event_name: String,
     pub seed: String,
     pub lock_pda: Pubkey,
-    pub lock_id: u32,
+    pub lock_id: u64,
     pub authority: Pubkey,
     pub spl_mint: Pubkey,
     pub spl_mint_metadata_pda: Pubkey,
@@ -53,13 +62,18 @@ pub struct CreateDexLockEvent {
     pub end_time: u64,
     pub lock_name: String,
     pub extra_data: String,
-    pub tge_bps: u8,
+    pub tge_bps: u16,
     pub cycle: u64,
-    pub cycle_bps: u8,
+    pub cycle_bps: u16,
     pub dex_name: String,
     pub token_mint_a: Pubkey,
     pub token_mint_b: Pubkey,
     pub position_mint: Pubkey,
+    pub fee_lamports: u64,
+    pub fee_amount: u64,
+    pub transfer_fee: u64,
+    pub pool: Pubkey,
+    pub verified: bool,
 }
 
 #[event]
@@ -67,8 +81,9 @@ pub struct UnlockLpEvent {
     pub event_name: String,
     pub seed: String,
     pub lock_pda: Pubkey,
-    pub lock_id: u32,
+    pub lock_id: u64,
     pub authority: Pubkey,
+    pub unlocked_by: Pubkey, //the authority or its delegate
     pub spl_mint: Pubkey,
     pub spl_mint_metadata_pda: Pubkey,
     pub lock_amount: u64,
@@ -76,9 +91,9 @@ pub struct UnlockLpEvent {
     pub end_time: u64,
     pub lock_name: String,
     pub extra_data: String,
-    pub tge_bps: u8,
+    pub tge_bps: u16,
     pub cycle: u64,
-    pub cycle_bps: u8,
+    pub cycle_bps: u16,
     pub dex_name: String,
     pub token_mint_a: Pubkey,
     pub token_mint_b: Pubkey,
@@ -90,7 +105,7 @@ pub struct LockVestingEvent {
     pub event_name: String,
     pub seed: String,
     pub lock_pda: Pubkey,
-    pub lock_id: u32,
+    pub lock_id: u64,
     pub authority: Pubkey,
     pub spl_mint: Pubkey,
     pub spl_mint_metadata_pda: Pubkey,
@@ -99,15 +114,17 @@ pub struct LockVestingEvent {
     pub end_time: u64,
     pub lock_name: String,
     pub extra_data: String,
-    pub tge_bps: u8,
+    pub tge_bps: u16,
     pub cycle: u64,
-    pub cycle_bps: u8,
+    pub cycle_bps: u16,
     pub dex_name: String,
-    pub first_release: f64,
-    pub vesting_period: u64,
-    pub amount_per_vesting: f64,
+    pub vesting_type: VestingType,
+    pub vesting_end_time: u64,
     pub user_list: Vec<Pubkey>,
     pub user_amount: Vec<u64>,
+    pub fee_lamports: u64,
+    pub fee_amount: u64,
+    pub transfer_fee: u64,
 }
 
 #[event]
@@ -115,7 +132,7 @@ pub struct UnlockVestingEvent {
     pub event_name: String,
     pub seed: String,
     pub lock_pda: Pubkey,
-    pub lock_id: u32,
+    pub lock_id: u64,
     pub authority: Pubkey,
     pub spl_mint: Pubkey,
     pub spl_mint_metadata_pda: Pubkey,
@@ -124,15 +141,84 @@ pub struct UnlockVestingEvent {
     pub end_time: u64,
     pub lock_name: String,
     pub extra_data: String,
-    pub tge_bps: u8,
+    pub tge_bps: u16,
     pub cycle: u64,
-    pub cycle_bps: u8,
-    pub first_release: f64,
-    pub vesting_period: u64,
-    pub amount_per_vesting: f64,
-    pub user_list: Vec<Pubkey>,
-    pub user_amount: Vec<u64>,
+    pub cycle_bps: u16,
+    pub vesting_type: VestingType,
+    pub vesting_end_time: u64,
+    pub beneficiary: Pubkey,
+    pub beneficiary_amount: u64,
+    pub released_amount: u64,
     pub unlocker: Pubkey,
+    pub transfer_fee: u64,
+}
+
+#[event]
+pub struct IncreaseLockAmountEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub authority: Pubkey,
+    pub spl_mint: Pubkey,
+    pub amount: u64,
+    pub lock_amount: u64,
+    pub end_time: u64,
+    pub fee_amount: u64,
+    pub transfer_fee: u64,
+}
+
+#[event]
+pub struct MigrateLockTimeEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+#[event]
+pub struct MigrateLockAccountEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub lock_kind: String, //"token", "lp" or "vesting"
+    pub authority: Pubkey,
+    pub spl_mint: Pubkey,
+    pub lock_amount: u64,
+    pub end_time: u64,
+    pub beneficiary_count: u32,
+}
+
+#[event]
+pub struct CloseLockEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+}
+
+#[event]
+pub struct ProposeLockOwnershipEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey, //Pubkey::default() when a pending transfer is cancelled
+}
+
+#[event]
+pub struct TransferLockOwnershipEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
 }
 
 #[event]
@@ -140,5 +226,108 @@ pub struct ExtendLockTimeEvent {
     pub event_name: String,
     pub seed: String,
     pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub authority: Pubkey,
+    pub spl_mint: Pubkey,
+    pub old_end_time: u64,
+    pub end_time: u64,
+    pub vesting_end_time: u64, //shifted with end_time for linear vesting, 0 for other locks
+}
+
+#[event]
+pub struct ConfigUpdateEvent {
+    pub event_name: String,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub paused: bool,
+    pub max_vesting_beneficiaries: u32,
+    pub max_lock_duration: u64,
+    pub treasury: Pubkey,
+    pub flat_fee_lamports: u64,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct FeeExemptionEvent {
+    pub event_name: String,
+    pub target: Pubkey,
+    pub exempt: bool,
+}
+
+#[event]
+pub struct CreateVestingPoolEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub authority: Pubkey,
+    pub spl_mint: Pubkey,
+    pub start_time: u64,
     pub end_time: u64,
+    pub lock_name: String,
+    pub extra_data: String,
+    pub tge_bps: u16,
+    pub cycle: u64,
+    pub cycle_bps: u16,
+    pub vesting_type: VestingType,
+    pub vesting_end_time: u64,
+    pub fee_lamports: u64,
+}
+
+#[event]
+pub struct AddBeneficiariesEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub user_list: Vec<Pubkey>,
+    pub user_amount: Vec<u64>,
+    pub lock_amount: u64,
+    pub beneficiary_count: u32,
+    pub fee_amount: u64,
+    pub transfer_fee: u64,
+}
+
+#[event]
+pub struct FinalizeVestingPoolEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub lock_amount: u64,
+    pub beneficiary_count: u32,
+}
+
+#[event]
+pub struct SetMerkleRootEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub merkle_root: [u8; 32],
+    pub lock_amount: u64,
+    pub fee_amount: u64,
+    pub transfer_fee: u64,
+}
+
+#[event]
+pub struct CollectPositionFeesEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub authority: Pubkey,
+    pub dex_program: Pubkey,
+    pub position: Pubkey,
+    pub reward_index: Option<u8>,
+}
+
+#[event]
+pub struct SetLpDelegateEvent {
+    pub event_name: String,
+    pub seed: String,
+    pub lock_pda: Pubkey,
+    pub lock_id: u64,
+    pub authority: Pubkey,
+    pub delegate: Pubkey,
 }
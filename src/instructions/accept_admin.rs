@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.pending_admin == pending_admin.key() @ LockError::AuthorizationErr
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub pending_admin: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    config.admin = ctx.accounts.pending_admin.key();
+    config.pending_admin = Pubkey::default();
+
+    emit!(ConfigUpdateEvent {
+        event_name: "accept_admin".to_string(),
+        admin: config.admin,
+        pending_admin: config.pending_admin,
+        paused: config.paused,
+        max_vesting_beneficiaries: config.max_vesting_beneficiaries,
+        max_lock_duration: config.max_lock_duration,
+        treasury: config.treasury,
+        flat_fee_lamports: config.flat_fee_lamports,
+        fee_bps: config.fee_bps,
+    });
+
+    Ok(())
+}
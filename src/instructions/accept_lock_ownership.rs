@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct AcceptLockOwnership<'info> {
+    #[account(mut)]
+    pub token_lock: Option<Box<Account<'info, TokenLock>>>,
+
+    #[account(mut)]
+    pub lp_lock: Option<Box<Account<'info, LpLock>>>,
+
+    #[account(mut)]
+    pub vesting_lock: Option<Box<Account<'info, VestingLock>>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub lock_index: OwnerIndexMove<'info>,
+
+    #[account(mut)]
+    pub pending_authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<AcceptLockOwnership>) -> Result<()> {
+    let (lock_pda, header) = lock_header(
+        &mut ctx.accounts.token_lock,
+        &mut ctx.accounts.lp_lock,
+        &mut ctx.accounts.vesting_lock
+    )?;
+
+    if header.pending_authority != ctx.accounts.pending_authority.key() {
+        return Err(LockError::AuthorizationErr.into());
+    }
+
+    let previous_authority = header.authority;
+    header.authority = ctx.accounts.pending_authority.key();
+    header.pending_authority = Pubkey::default();
+
+    ctx.accounts.lock_index.move_lock(
+        lock_pda.key(),
+        previous_authority,
+        header.authority,
+        ctx.accounts.pending_authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    emit!(TransferLockOwnershipEvent {
+        event_name: "transfer_lock_ownership".to_string(),
+        seed: header.seed.clone(),
+        lock_pda: lock_pda.key(),
+        lock_id: header.lock_id,
+        previous_authority,
+        new_authority: header.authority,
+    });
+
+    //a delegate approved by the previous authority doesn't carry over
+    if let Some(lp_lock) = &mut ctx.accounts.lp_lock {
+        lp_lock.delegate = Pubkey::default();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface },
+};
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct AddBeneficiaries<'info> {
+    #[account(
+        mut,
+        seeds = [lock_pda.header.seed.as_ref(), spl_mint.key().as_ref(), lock_pda.header.creator.as_ref()],
+        bump = lock_pda.header.bump,
+        constraint = lock_pda.header.authority == authority.key() @ LockError::AuthorizationErr,
+        constraint = !lock_pda.finalized @ LockError::VestingPoolFinalized,
+        constraint = lock_pda.merkle_root == [0u8; 32] @ LockError::MerkleRootAlreadySet
+    )]
+    pub lock_pda: Box<Account<'info, VestingLock>>,
+
+    ///CHECK: checked in the handler, MintLockStats at [MINT_STATS_SEED, spl_mint]
+    #[account(mut)]
+    pub mint_stats: AccountInfo<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub spl_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = spl_mint,
+        token::authority = lock_pda
+    )]
+    pub lock_pda_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub authority_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: checked against config.treasury
+    #[account(mut, address = config.treasury @ LockError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+
+    //only needed when a token fee is charged
+    #[account(
+        mut,
+        token::mint = spl_mint,
+        token::authority = treasury
+    )]
+    pub treasury_spl_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_exemption: Option<Account<'info, FeeExemption>>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+//remaining accounts are the VestingBeneficiary pdas for user_list, in the same order,
+//followed by the transfer hook accounts of the mint
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AddBeneficiaries<'info>>,
+    user_list: Vec<Pubkey>,
+    user_amount: Vec<u64>
+) -> Result<()> {
+    let lock_pda = &mut ctx.accounts.lock_pda;
+
+    let beneficiary_count = lock_pda.beneficiary_count
+        .checked_add(user_list.len() as u32)
+        .ok_or(LockError::MathOverflow)?;
+
+    if beneficiary_count > ctx.accounts.config.max_vesting_beneficiaries {
+        return Err(LockError::TooManyBeneficiaries.into());
+    }
+
+    if ctx.remaining_accounts.len() < user_list.len() {
+        return Err(LockError::InvalidBeneficiaryAccount.into());
+    }
+    let (beneficiary_accounts, hook_accounts) = ctx.remaining_accounts.split_at(user_list.len());
+
+    let amount = create_beneficiaries(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        lock_pda.key(),
+        beneficiary_accounts,
+        &user_list,
+        &user_amount
+    )?;
+
+    if amount == 0 {
+        return Err(LockError::AmountZero.into());
+    }
+
+    lock_pda.header.lock_amount = lock_pda.header.lock_amount
+        .checked_add(amount)
+        .ok_or(LockError::MathOverflow)?;
+    add_locked_amount(&ctx.accounts.mint_stats, &lock_pda.header, amount)?;
+    lock_pda.beneficiary_count = beneficiary_count;
+
+    //the vault must hold every beneficiary amount, so the sender covers the transfer fee
+    let gross_amount = amount_with_transfer_fee(&ctx.accounts.spl_mint, amount)?;
+    let vault_before = ctx.accounts.lock_pda_spl_ata.amount;
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.authority_spl_ata.to_account_info(),
+        ctx.accounts.lock_pda_spl_ata.to_account_info(),
+        &ctx.accounts.spl_mint,
+        ctx.accounts.token_program.to_account_info(),
+        gross_amount,
+        hook_accounts
+    )?;
+
+    let received = received_amount(&mut ctx.accounts.lock_pda_spl_ata, vault_before)?;
+    if received < amount {
+        return Err(LockError::LowBalance.into());
+    }
+    let transfer_fee = gross_amount - received;
+
+    //the flat fee was paid when the pool was created
+    let (_, fee_amount) = lock_fees(
+        &ctx.accounts.config,
+        &ctx.accounts.fee_exemption,
+        &[ctx.accounts.spl_mint.key(), ctx.accounts.authority.key()],
+        amount
+    )?;
+
+    if let Some(treasury_spl_ata) = fee_token_account(&ctx.accounts.treasury_spl_ata, fee_amount)? {
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.authority_spl_ata.to_account_info(),
+            treasury_spl_ata,
+            &ctx.accounts.spl_mint,
+            ctx.accounts.token_program.to_account_info(),
+            fee_amount,
+            hook_accounts
+        )?;
+    }
+
+    emit!(AddBeneficiariesEvent {
+        event_name: "add_beneficiaries".to_string(),
+        seed: lock_pda.header.seed.clone(),
+        lock_pda: lock_pda.key(),
+        lock_id: lock_pda.header.lock_id,
+        user_list,
+        user_amount,
+        lock_amount: lock_pda.header.lock_amount,
+        beneficiary_count,
+        fee_amount,
+        transfer_fee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+
+// Accounts
+#[derive(Accounts)]
+#[instruction(target:Pubkey)]
+pub struct AddFeeExemption<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ LockError::AuthorizationErr
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    #[account(
+        init,
+        space = 8 + FeeExemption::INIT_SPACE,
+        seeds = [FEE_EXEMPTION_SEED, target.as_ref()],
+        bump,
+        payer = admin
+    )]
+    pub fee_exemption: Box<Account<'info, FeeExemption>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<AddFeeExemption>, target: Pubkey) -> Result<()> {
+    let fee_exemption = &mut ctx.accounts.fee_exemption;
+
+    fee_exemption.bump = ctx.bumps.fee_exemption;
+    fee_exemption.target = target;
+
+    emit!(FeeExemptionEvent {
+        event_name: "add_fee_exemption".to_string(),
+        target,
+        exempt: true,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::token_interface::{ self, CloseAccount, Mint, TokenAccount, TokenInterface };
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct CloseLock<'info> {
+    #[account(mut)]
+    pub token_lock: Option<Box<Account<'info, TokenLock>>>,
+
+    #[account(mut)]
+    pub lp_lock: Option<Box<Account<'info, LpLock>>>,
+
+    #[account(mut)]
+    pub vesting_lock: Option<Box<Account<'info, VestingLock>>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    //the lookup of the lock's id, closed with it
+    #[account(mut)]
+    pub lock_id_lookup: Box<Account<'info, LockIdLookup>>,
+
+    pub lock_index: LockIndexPages<'info>,
+
+    #[account(mut)]
+    pub lock_pda_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //only needed when the vault holds withheld transfer fees, they are harvested to the mint
+    #[account(mut)]
+    pub vault_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    pub authority: Signer<'info>,
+
+    ///CHECK: checked against the lock payer
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+//a vesting lock takes its VestingBeneficiary pdas as remaining accounts and closes them first.
+//when they don't fit in one transaction, the lock stays open until the call closing the last one.
+pub(crate) fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, CloseLock<'info>>) -> Result<()> {
+    let (lock_pda, header) = lock_header(
+        &mut ctx.accounts.token_lock,
+        &mut ctx.accounts.lp_lock,
+        &mut ctx.accounts.vesting_lock
+    )?;
+
+    if header.authority != ctx.accounts.authority.key() || header.payer != ctx.accounts.payer.key() {
+        return Err(LockError::AuthorizationErr.into());
+    }
+
+    require_keys_eq!(ctx.accounts.lock_id_lookup.lock, lock_pda.key(), LockError::InvalidLockIdAccount);
+
+    let lock_pda_spl_ata = &ctx.accounts.lock_pda_spl_ata;
+    require_keys_eq!(lock_pda_spl_ata.owner, lock_pda.key(), LockError::AuthorizationErr);
+    require_keys_eq!(lock_pda_spl_ata.mint, header.vault_mint, LockError::SplMintError);
+
+    if header.lock_amount > 0 || lock_pda_spl_ata.amount > 0 {
+        return Err(LockError::LockNotEmpty.into());
+    }
+
+    let lock_key = lock_pda.key();
+    if let Some(vesting_lock) = &mut ctx.accounts.vesting_lock {
+        close_beneficiaries(vesting_lock, lock_key, ctx.remaining_accounts, &ctx.accounts.payer)?;
+        if vesting_lock.beneficiary_count > 0 {
+            return Ok(());
+        }
+    }
+
+    let (lock_pda, header) = lock_header(
+        &mut ctx.accounts.token_lock,
+        &mut ctx.accounts.lp_lock,
+        &mut ctx.accounts.vesting_lock
+    )?;
+    let creator = header.creator;
+    let seed_mint = header.seed_mint;
+    let lock_bump = header.bump;
+    let seeds = &[header.seed.as_ref(), seed_mint.as_ref(), creator.as_ref(), &[lock_bump]];
+    let signer = &[&seeds[..]];
+
+    //token-2022 refuses to close an account holding withheld transfer fees
+    if withheld_transfer_fees(lock_pda_spl_ata)? > 0 {
+        let vault_mint = ctx.accounts.vault_mint.as_ref().ok_or(LockError::WithheldTransferFees)?;
+        require_keys_eq!(vault_mint.key(), header.vault_mint, LockError::SplMintError);
+        harvest_withheld_fees(
+            ctx.accounts.token_program.to_account_info(),
+            vault_mint.to_account_info(),
+            lock_pda_spl_ata.to_account_info()
+        )?;
+    }
+
+    token_interface::close_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: lock_pda_spl_ata.to_account_info(),
+                destination: ctx.accounts.payer.to_account_info(),
+                authority: lock_pda.clone(),
+            },
+            signer
+        )
+    )?;
+
+    emit!(CloseLockEvent {
+        event_name: "close_lock".to_string(),
+        seed: header.seed.clone(),
+        lock_pda: lock_pda.key(),
+        lock_id: header.lock_id,
+        authority: header.authority,
+        payer: header.payer,
+    });
+
+    ctx.accounts.lock_index.remove(lock_pda.key(), header)?;
+    ctx.accounts.lock_id_lookup.close(ctx.accounts.payer.to_account_info())?;
+
+    close_lock_account(
+        &ctx.accounts.token_lock,
+        &ctx.accounts.lp_lock,
+        &ctx.accounts.vesting_lock,
+        ctx.accounts.payer.to_account_info()
+    )
+}
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{ instruction::{ AccountMeta, Instruction }, program::invoke_signed };
+
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::dex;
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+
+// Accounts
+#[derive(Accounts)]
+#[instruction(input:String)]
+pub struct CollectPositionFees<'info> {
+    #[account(
+        seeds = [input.as_ref(), lock_pda.header.seed_mint.as_ref(), lock_pda.header.creator.as_ref()],
+        bump = lock_pda.header.bump,
+        constraint = lock_pda.header.authority == authority.key() @ LockError::AuthorizationErr,
+        constraint = lock_pda.dex_program != Pubkey::default() @ LockError::NotDexPosition
+    )]
+    pub lock_pda: Box<Account<'info, LpLock>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        token::mint = lock_pda.header.vault_mint,
+        token::authority = lock_pda
+    )]
+    pub lock_pda_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: checked against lock_pda.dex_program
+    #[account(address = lock_pda.dex_program @ LockError::UnsupportedDex)]
+    pub dex_program: AccountInfo<'info>,
+}
+
+//remaining accounts are the accounts of the dex collect instruction, in the order the dex expects.
+//the lock pda signs as the position owner, the instruction data is built here and only
+//collects fees or rewards, so the locked liquidity can't be withdrawn.
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, CollectPositionFees<'info>>,
+    input: String,
+    reward_index: Option<u8>
+) -> Result<()> {
+    let lock_pda = &ctx.accounts.lock_pda;
+    let dex_program = lock_pda.dex_program;
+
+    dex::check_collect_accounts(
+        &dex_program,
+        ctx.remaining_accounts,
+        &lock_pda.key(),
+        &ctx.accounts.lock_pda_spl_ata.key(),
+        &lock_pda.position,
+        &lock_pda.pool
+    )?;
+
+    let accounts = ctx.remaining_accounts
+        .iter()
+        .map(|account| {
+            let is_signer = account.is_signer || account.key() == lock_pda.key();
+            if account.is_writable {
+                AccountMeta::new(account.key(), is_signer)
+            } else {
+                AccountMeta::new_readonly(account.key(), is_signer)
+            }
+        })
+        .collect();
+
+    let instruction = Instruction {
+        program_id: dex_program,
+        accounts,
+        data: dex::collect_data(&dex_program, reward_index)?,
+    };
+
+    let creator = lock_pda.header.creator;
+    let seed_mint = lock_pda.header.seed_mint;
+    let lock_bump = lock_pda.header.bump;
+    let seeds = &[input.as_ref(), seed_mint.as_ref(), creator.as_ref(), &[lock_bump]];
+
+    let mut account_infos = ctx.remaining_accounts.to_vec();
+    account_infos.push(ctx.accounts.dex_program.to_account_info());
+    invoke_signed(&instruction, &account_infos, &[&seeds[..]])?;
+
+    emit!(CollectPositionFeesEvent {
+        event_name: "collect_position_fees".to_string(),
+        seed: lock_pda.header.seed.clone(),
+        lock_pda: lock_pda.key(),
+        lock_id: lock_pda.header.lock_id,
+        authority: lock_pda.header.authority,
+        dex_program,
+        position: lock_pda.position,
+        reward_index,
+    });
+
+    Ok(())
+}
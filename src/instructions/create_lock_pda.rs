@@ -1,9 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{ token::{ self, Mint, Token, TokenAccount }, associated_token::AssociatedToken };
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface },
+};
 
+use crate::dex;
 use crate::state::*;
 use crate::error::*;
 use crate::event::*;
+use crate::utils::*;
 
 // Accounts
 #[derive(Accounts)]
@@ -11,23 +16,33 @@ use crate::event::*;
 pub struct LockTest<'info> {
     #[account(
         init,
-        space = 8 + LockPda::LEN,
+        space = 8 + LpLock::INIT_SPACE,
         seeds = [input.as_ref(), spl_mint.key().as_ref(), lock_pda_authority.key().as_ref()],
         bump,
         payer = lock_pda_authority
     )]
-    pub lock_pda: Box<Account<'info, LockPda>>,
+    pub lock_pda: Box<Account<'info, LpLock>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub lock_ids: LockIds<'info>,
+
+    pub lock_index: LockIndex<'info>,
 
     #[account(mut)]
     pub authority: Signer<'info>,
     
-    ///CHECK:safe
     #[account(mut)]
     pub lock_pda_authority: Signer<'info>,
 
-    pub spl_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+    pub spl_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    pub position_mint: Account<'info, Mint>,
+    pub position_mint: Box<InterfaceAccount<'info, Mint>>,
 
     ///CHECK:safe
     pub spl_mint_metadata_pda: AccountInfo<'info>,
@@ -38,22 +53,40 @@ pub struct LockTest<'info> {
         associated_token::mint = position_mint,
         associated_token::authority = lock_pda
     )]
-    pub lock_pda_spl_ata: Box<Account<'info, TokenAccount>>,
+    pub lock_pda_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
-    pub authority_spl_ata: Box<Account<'info, TokenAccount>>,
+    pub authority_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: optional, owner and layout checked in the handler
+    pub pool: Option<AccountInfo<'info>>,
 
-    pub token_program: Program<'info, Token>,
+    ///CHECK: optional, the clmm position account when verifying a position nft
+    pub position: Option<AccountInfo<'info>>,
 
-    pub token_2022_program: Interface<'info, anchor_spl::token_interface:: TokenInterface>,
+    ///CHECK: checked against config.treasury
+    #[account(mut, address = config.treasury @ LockError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+
+    //only needed when a token fee is charged
+    #[account(
+        mut,
+        token::mint = position_mint,
+        token::authority = treasury
+    )]
+    pub treasury_spl_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_exemption: Option<Account<'info, FeeExemption>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn handler(
-    ctx: Context<LockTest>,
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, LockTest<'info>>,
     input: String,
     lock_amount: u64,
     lock_time: u64,
@@ -69,31 +102,25 @@ pub fn handler(
         return Err(LockError::AmountZero.into());
     }
 
+    let mint_policy = check_mint_extensions(&ctx.accounts.position_mint)?;
+
+    //with a pool, the dex name and pool mints are read on chain instead of trusted from the caller
+    let verified_pool = match &ctx.accounts.pool {
+        Some(pool) =>
+            Some(dex::verify_lp_mint(pool, ctx.accounts.position.as_ref(), &ctx.accounts.position_mint.key())?),
+        None => None,
+    };
+    let (dex_name, token_mint_a, token_mint_b) = match &verified_pool {
+        Some(verified_pool) => (verified_pool.dex_name.to_string(), verified_pool.token_mint_a, verified_pool.token_mint_b),
+        None => (dex_name, token_mint_a, token_mint_b),
+    };
+
     if lock_time == 0 {
         return Err(LockError::TimeZero.into());
     }
 
-    if lock_pda.lock_amount > 0 {
-        return Err(LockError::AlreadyLocked.into());
-    }
-
-    //check spl_mint fllows metaplex standard
-    // let expected = Metadata::find_pda(&ctx.accounts.spl_mint.key()).0;
-
-    // let metadata_pda = &ctx.accounts.spl_mint_metadata_pda;
-    // if metadata_pda.key() != expected {
-    //     return Err(LockError::NotValidToken.into());
-    // }
-
-    // if metadata_pda.data_len() == 0 {
-    //     return Err(LockError::NotValidToken.into());
-    // }
-
-    // if metadata_pda.data_len() != 0 {
-    //     if metadata_pda.owner != mpl_token_metadata::ID {
-    //         return Err(VendingMachineError::NotValidToken.into());
-    //     }
-    // }
+    //check spl_mint_metadata_pda is the metadata of the mint
+    check_mint_metadata(&ctx.accounts.position_mint, &ctx.accounts.spl_mint_metadata_pda)?;
 
     let clock = Clock::get();
     let block_time = clock.unwrap().unix_timestamp as u64;
@@ -102,54 +129,123 @@ pub fn handler(
         return Err(LockError::BeforeNow.into());
     }
 
-    lock_pda.bump = ctx.bumps.lock_pda;
-    lock_pda.seed = input.clone();
-    lock_pda.authority = ctx.accounts.authority.key();
-    lock_pda.spl_mint = token_mint_a;
-    lock_pda.spl_mint_metadata_pda = ctx.accounts.spl_mint_metadata_pda.key();
-    lock_pda.lock_amount = lock_amount;
-    lock_pda.start_time = block_time;
-    lock_pda.end_time = lock_time;
-    lock_pda.lock_name = lock_name;
-    lock_pda.extra_data = extra_data;
+    ctx.accounts.config.check_lock_duration(block_time, lock_time)?;
+
+    let lock_id = ctx.accounts.lock_ids.assign(
+        lock_pda.key(),
+        ctx.accounts.lock_pda_authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    let header = &mut lock_pda.header;
+    header.bump = ctx.bumps.lock_pda;
+    header.lock_id = lock_id;
+    header.seed = input;
+    header.creator = ctx.accounts.lock_pda_authority.key();
+    header.authority = ctx.accounts.authority.key();
+    header.payer = ctx.accounts.lock_pda_authority.key();
+    header.spl_mint = token_mint_a;
+    header.vault_mint = ctx.accounts.position_mint.key();
+    header.seed_mint = ctx.accounts.spl_mint.key();
+    header.spl_mint_metadata_pda = ctx.accounts.spl_mint_metadata_pda.key();
+    header.permanent_delegate = mint_policy.permanent_delegate;
+    header.start_time = block_time;
+    header.end_time = lock_time;
+    header.lock_name = lock_name;
+    header.extra_data = extra_data;
+
     lock_pda.dex_name = dex_name;
     lock_pda.token_mint_a = token_mint_a;
     lock_pda.token_mint_b = token_mint_b;
-    lock_pda.position_mint = ctx.accounts.position_mint.key();
+    if let Some(verified_pool) = &verified_pool {
+        verified_pool.record(lock_pda);
+    }
+
+    //like lp locks made with lock_token, the token fee comes out of lock_amount
+    let (fee_lamports, fee_amount) = lock_fees(
+        &ctx.accounts.config,
+        &ctx.accounts.fee_exemption,
+        &exempt_keys(
+            ctx.accounts.position_mint.key(),
+            ctx.accounts.authority.key(),
+            verified_pool.as_ref().map(|verified_pool| (verified_pool.token_mint_a, verified_pool.token_mint_b))
+        ),
+        lock_amount
+    )?;
+    let deposit = lock_amount - fee_amount;
+
+    if deposit == 0 {
+        return Err(LockError::AmountZero.into());
+    }
 
     // transfer the tokens
-    let token_ctx = CpiContext::new(
+    let vault_before = ctx.accounts.lock_pda_spl_ata.amount;
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.authority_spl_ata.to_account_info(),
+        ctx.accounts.lock_pda_spl_ata.to_account_info(),
+        &ctx.accounts.position_mint,
         ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
-            authority: ctx.accounts.authority.to_account_info(),
-            from: ctx.accounts.authority_spl_ata.to_account_info(),
-            to: ctx.accounts.lock_pda_spl_ata.to_account_info(),
-        }
-    );
-    token::transfer(token_ctx, lock_amount)?;
+        deposit,
+        ctx.remaining_accounts
+    )?;
+
+    let received = received_amount(&mut ctx.accounts.lock_pda_spl_ata, vault_before)?;
+    let transfer_fee = deposit - received;
+    lock_pda.header.lock_amount = received;
+
+    transfer_fee_lamports(
+        ctx.accounts.lock_pda_authority.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        fee_lamports
+    )?;
+
+    if let Some(treasury_spl_ata) = fee_token_account(&ctx.accounts.treasury_spl_ata, fee_amount)? {
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.authority_spl_ata.to_account_info(),
+            treasury_spl_ata,
+            &ctx.accounts.position_mint,
+            ctx.accounts.token_program.to_account_info(),
+            fee_amount,
+            ctx.remaining_accounts
+        )?;
+    }
 
-    lock_pda.lock_id = block_time as u32;
+    ctx.accounts.lock_index.record(
+        lock_pda.key(),
+        &lock_pda.header,
+        ctx.accounts.lock_pda_authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
 
+    let header = &lock_pda.header;
     emit!(CreateDexLockEvent {
         event_name: "create_dex_lock".to_string(),
         lock_pda: lock_pda.key(),
-        seed: lock_pda.seed.clone(),
-        lock_id: lock_pda.lock_id,
-        authority: lock_pda.authority,
-        spl_mint: lock_pda.spl_mint,
-        spl_mint_metadata_pda: lock_pda.spl_mint_metadata_pda,
-        lock_amount: lock_pda.lock_amount,
-        start_time: lock_pda.start_time,
-        end_time: lock_pda.end_time,
-        lock_name: lock_pda.lock_name.clone(),
-        extra_data: lock_pda.extra_data.clone(),
+        seed: header.seed.clone(),
+        lock_id: header.lock_id,
+        authority: header.authority,
+        spl_mint: header.spl_mint,
+        spl_mint_metadata_pda: header.spl_mint_metadata_pda,
+        lock_amount: header.lock_amount,
+        start_time: header.start_time,
+        end_time: header.end_time,
+        lock_name: header.lock_name.clone(),
+        extra_data: header.extra_data.clone(),
         tge_bps: 0,
         cycle: 0,
         cycle_bps: 0,
         dex_name: lock_pda.dex_name.clone(),
         token_mint_a: lock_pda.token_mint_a,
         token_mint_b: lock_pda.token_mint_b,
-        position_mint: lock_pda.position_mint,
+        position_mint: header.vault_mint,
+        fee_lamports,
+        fee_amount,
+        transfer_fee,
+        pool: lock_pda.pool,
+        verified: lock_pda.verified,
     });
 
     Ok(())
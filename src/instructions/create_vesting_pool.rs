@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface },
+};
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+// Accounts
+#[derive(Accounts)]
+#[instruction(input:String)]
+pub struct CreateVestingPool<'info> {
+    #[account(
+        init,
+        space = 8 + VestingLock::INIT_SPACE,
+        seeds = [input.as_ref(), spl_mint.key().as_ref(), authority.key().as_ref()],
+        bump,
+        payer = authority
+    )]
+    pub lock_pda: Box<Account<'info, VestingLock>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub lock_ids: LockIds<'info>,
+
+    pub lock_index: LockIndex<'info>,
+
+    pub spl_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    ///CHECK:safe
+    pub spl_mint_metadata_pda: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = spl_mint,
+        associated_token::authority = lock_pda
+    )]
+    pub lock_pda_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: checked against config.treasury
+    #[account(mut, address = config.treasury @ LockError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+
+    pub fee_exemption: Option<Account<'info, FeeExemption>>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+//creates an empty vesting lock, beneficiaries are added in batches with add_beneficiaries
+pub(crate) fn handler(
+    ctx: Context<CreateVestingPool>,
+    input: String,
+    lock_time: u64,
+    lock_name: String,
+    extra_data: String,
+    schedule: VestingSchedule
+) -> Result<()> {
+    let lock_pda = &mut ctx.accounts.lock_pda;
+
+    if lock_time == 0 {
+        return Err(LockError::TimeZero.into());
+    }
+
+    let mint_policy = check_mint_extensions(&ctx.accounts.spl_mint)?;
+    check_mint_metadata(&ctx.accounts.spl_mint, &ctx.accounts.spl_mint_metadata_pda)?;
+
+    let clock = Clock::get();
+    let block_time = clock.unwrap().unix_timestamp as u64;
+
+    if lock_time <= block_time {
+        return Err(LockError::BeforeNow.into());
+    }
+
+    ctx.accounts.config.check_lock_duration(block_time, lock_time)?;
+
+    schedule.validate(lock_time)?;
+
+    let lock_id = ctx.accounts.lock_ids.assign(
+        lock_pda.key(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    let header = &mut lock_pda.header;
+    header.bump = ctx.bumps.lock_pda;
+    header.lock_id = lock_id;
+    header.seed = input;
+    header.creator = ctx.accounts.authority.key();
+    header.authority = ctx.accounts.authority.key();
+    header.payer = ctx.accounts.authority.key();
+    header.spl_mint = ctx.accounts.spl_mint.key();
+    header.vault_mint = ctx.accounts.spl_mint.key();
+    header.seed_mint = ctx.accounts.spl_mint.key();
+    header.spl_mint_metadata_pda = ctx.accounts.spl_mint_metadata_pda.key();
+    header.permanent_delegate = mint_policy.permanent_delegate;
+    header.start_time = block_time;
+    header.end_time = lock_time;
+    header.lock_name = lock_name;
+    header.extra_data = extra_data;
+
+    lock_pda.set_schedule(&schedule);
+
+    //the flat fee is paid once here, add_beneficiaries charges the token fee per batch
+    let (fee_lamports, _) = lock_fees(
+        &ctx.accounts.config,
+        &ctx.accounts.fee_exemption,
+        &[ctx.accounts.spl_mint.key(), ctx.accounts.authority.key()],
+        0
+    )?;
+
+    transfer_fee_lamports(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        fee_lamports
+    )?;
+
+    ctx.accounts.lock_index.record(
+        lock_pda.key(),
+        &lock_pda.header,
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    let header = &lock_pda.header;
+    emit!(CreateVestingPoolEvent {
+        event_name: "create_vesting_pool".to_string(),
+        seed: header.seed.clone(),
+        lock_pda: lock_pda.key(),
+        lock_id: header.lock_id,
+        authority: header.authority,
+        spl_mint: header.spl_mint,
+        start_time: header.start_time,
+        end_time: header.end_time,
+        lock_name: header.lock_name.clone(),
+        extra_data: header.extra_data.clone(),
+        tge_bps: lock_pda.tge_bps,
+        cycle: lock_pda.vesting_period,
+        cycle_bps: lock_pda.cycle_bps,
+        vesting_type: lock_pda.vesting_type,
+        vesting_end_time: lock_pda.vesting_end_time,
+        fee_lamports,
+    });
+
+    Ok(())
+}
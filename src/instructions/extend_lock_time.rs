@@ -1,53 +1,99 @@
 use anchor_lang::prelude::*;
 
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_interface:: TokenInterface ,
-};
-
 use crate::state::*;
 use crate::event::*;
 use crate::error::*;
+use crate::utils::*;
 
 
 // Accounts
 #[derive(Accounts)]
 #[instruction()]
 pub struct ExtendLockTime<'info> {
+    #[account(mut)]
+    pub token_lock: Option<Box<Account<'info, TokenLock>>>,
+
+    #[account(mut)]
+    pub lp_lock: Option<Box<Account<'info, LpLock>>>,
+
+    #[account(mut)]
+    pub vesting_lock: Option<Box<Account<'info, VestingLock>>>,
+
     #[account(
-        mut,
-        seeds = [lock_pda.seed.as_ref(), lock_pda.spl_mint.as_ref(), authority.key().as_ref()],
-        bump,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
     )]
-    pub lock_pda: Box<Account<'info, LockPda>>,
+    pub config: Box<Account<'info, ProgramConfig>>,
 
     #[account(mut)]
     pub authority: Signer<'info>,
-
-    pub token_program: Interface<'info, TokenInterface>,
-
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 
-pub fn handler(
+pub(crate) fn handler(
     ctx: Context<ExtendLockTime>,
     lock_time: u64,
 ) -> Result<()> {
-    let lock_pda = &mut ctx.accounts.lock_pda;
-    if lock_time <lock_pda.end_time{
+    let clock = Clock::get();
+    let block_time = clock.unwrap().unix_timestamp as u64;
+
+    //beneficiaries may already have claimed, vesting locks can only move before their cliff
+    if let Some(vesting_lock) = &ctx.accounts.vesting_lock {
+        if block_time >= vesting_lock.header.end_time {
+            return Err(LockError::VestingStarted.into());
+        }
+    }
+
+    let (lock_pda, header) = lock_header(
+        &mut ctx.accounts.token_lock,
+        &mut ctx.accounts.lp_lock,
+        &mut ctx.accounts.vesting_lock
+    )?;
+
+    if header.authority != ctx.accounts.authority.key() {
+        return Err(LockError::AuthorizationErr.into());
+    }
+
+    if lock_time <= header.end_time {
         return Err(LockError::NotBiggerThanEndTime.into());
     }
 
-    lock_pda.end_time = lock_time;
+    if lock_time <= block_time {
+        return Err(LockError::BeforeNow.into());
+    }
+
+    ctx.accounts.config.check_lock_duration(block_time, lock_time)?;
+
+    let old_end_time = header.end_time;
+    header.end_time = lock_time;
+
+    let seed = header.seed.clone();
+    let lock_id = header.lock_id;
+    let authority = header.authority;
+    let spl_mint = header.spl_mint;
+
+    //the schedule runs from end_time, so a linear release ends later by the same amount
+    let mut vesting_end_time = 0;
+    if let Some(vesting_lock) = &mut ctx.accounts.vesting_lock {
+        if vesting_lock.vesting_type == VestingType::Linear {
+            vesting_lock.vesting_end_time = vesting_lock.vesting_end_time
+                .checked_add(lock_time - old_end_time)
+                .ok_or(LockError::MathOverflow)?;
+        }
+        vesting_end_time = vesting_lock.vesting_end_time;
+    }
 
     emit!(ExtendLockTimeEvent {
         event_name: "extend_lock_time_event".to_string(),
-        seed: lock_pda.seed.clone(),
+        seed,
         lock_pda: lock_pda.key(),
-        end_time: lock_pda.end_time,
+        lock_id,
+        authority,
+        spl_mint,
+        old_end_time,
+        end_time: lock_time,
+        vesting_end_time,
     });
 
     Ok(())
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct FinalizeVestingPool<'info> {
+    #[account(
+        mut,
+        constraint = lock_pda.header.authority == authority.key() @ LockError::AuthorizationErr,
+        constraint = !lock_pda.finalized @ LockError::VestingPoolFinalized
+    )]
+    pub lock_pda: Box<Account<'info, VestingLock>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub authority: Signer<'info>,
+}
+
+//closes the beneficiary list, claims are only allowed after this
+pub(crate) fn handler(ctx: Context<FinalizeVestingPool>) -> Result<()> {
+    let lock_pda = &mut ctx.accounts.lock_pda;
+
+    if lock_pda.header.lock_amount == 0 {
+        return Err(LockError::AmountZero.into());
+    }
+
+    lock_pda.finalized = true;
+
+    emit!(FinalizeVestingPoolEvent {
+        event_name: "finalize_vesting_pool".to_string(),
+        seed: lock_pda.header.seed.clone(),
+        lock_pda: lock_pda.key(),
+        lock_id: lock_pda.header.lock_id,
+        lock_amount: lock_pda.header.lock_amount,
+        beneficiary_count: lock_pda.beneficiary_count,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface },
+};
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct IncreaseLockAmount<'info> {
+    #[account(mut)]
+    pub token_lock: Option<Box<Account<'info, TokenLock>>>,
+
+    #[account(mut)]
+    pub lp_lock: Option<Box<Account<'info, LpLock>>>,
+
+    #[account(mut)]
+    pub vesting_lock: Option<Box<Account<'info, VestingLock>>>,
+
+    ///CHECK: checked in the handler, MintLockStats at [MINT_STATS_SEED, spl_mint]
+    #[account(mut)]
+    pub mint_stats: AccountInfo<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub spl_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = spl_mint
+    )]
+    pub lock_pda_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub authority_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: checked against config.treasury
+    #[account(mut, address = config.treasury @ LockError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+
+    //only needed when a token fee is charged
+    #[account(
+        mut,
+        token::mint = spl_mint,
+        token::authority = treasury
+    )]
+    pub treasury_spl_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_exemption: Option<Account<'info, FeeExemption>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler<'info>(ctx: Context<'_, '_, '_, 'info, IncreaseLockAmount<'info>>, amount: u64) -> Result<()> {
+    //vesting locks split the amount between beneficiaries
+    if ctx.accounts.vesting_lock.is_some() {
+        return Err(LockError::NotTokenLock.into());
+    }
+
+    let pool_mints = ctx.accounts.lp_lock
+        .as_ref()
+        .filter(|lp_lock| lp_lock.verified)
+        .map(|lp_lock| (lp_lock.token_mint_a, lp_lock.token_mint_b));
+
+    let (lock_pda, header) = lock_header(
+        &mut ctx.accounts.token_lock,
+        &mut ctx.accounts.lp_lock,
+        &mut ctx.accounts.vesting_lock
+    )?;
+
+    if header.authority != ctx.accounts.authority.key() {
+        return Err(LockError::AuthorizationErr.into());
+    }
+
+    require_keys_eq!(ctx.accounts.spl_mint.key(), header.vault_mint, LockError::SplMintError);
+    require_keys_eq!(ctx.accounts.lock_pda_spl_ata.owner, lock_pda.key(), LockError::AuthorizationErr);
+
+    if amount == 0 {
+        return Err(LockError::AmountZero.into());
+    }
+
+    //same extension policy as creating the lock
+    check_mint_extensions(&ctx.accounts.spl_mint)?;
+
+    if header.lock_amount == 0 {
+        return Err(LockError::AlreadyUnlocked.into());
+    }
+
+    let vault_before = ctx.accounts.lock_pda_spl_ata.amount;
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.authority_spl_ata.to_account_info(),
+        ctx.accounts.lock_pda_spl_ata.to_account_info(),
+        &ctx.accounts.spl_mint,
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.remaining_accounts
+    )?;
+
+    let received = received_amount(&mut ctx.accounts.lock_pda_spl_ata, vault_before)?;
+    let transfer_fee = amount - received;
+
+    //only the token fee applies to top-ups, the flat fee was paid on creation
+    let (_, fee_amount) = lock_fees(
+        &ctx.accounts.config,
+        &ctx.accounts.fee_exemption,
+        &exempt_keys(ctx.accounts.spl_mint.key(), ctx.accounts.authority.key(), pool_mints),
+        amount
+    )?;
+
+    if let Some(treasury_spl_ata) = fee_token_account(&ctx.accounts.treasury_spl_ata, fee_amount)? {
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.authority_spl_ata.to_account_info(),
+            treasury_spl_ata,
+            &ctx.accounts.spl_mint,
+            ctx.accounts.token_program.to_account_info(),
+            fee_amount,
+            ctx.remaining_accounts
+        )?;
+    }
+
+    header.lock_amount = header.lock_amount
+        .checked_add(received)
+        .ok_or(LockError::MathOverflow)?;
+    add_locked_amount(&ctx.accounts.mint_stats, header, received)?;
+
+    emit!(IncreaseLockAmountEvent {
+        event_name: "increase_lock_amount".to_string(),
+        seed: header.seed.clone(),
+        lock_pda: lock_pda.key(),
+        lock_id: header.lock_id,
+        authority: header.authority,
+        spl_mint: ctx.accounts.spl_mint.key(),
+        amount,
+        lock_amount: header.lock_amount,
+        end_time: header.end_time,
+        fee_amount,
+        transfer_fee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::program::GempadSolanaLock;
+
+// Accounts
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        space = 8 + ProgramConfig::INIT_SPACE,
+        seeds = [CONFIG_SEED],
+        bump,
+        payer = authority
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, GempadSolanaLock>,
+
+    //only the upgrade authority can create the config
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ LockError::AuthorizationErr
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<InitializeConfig>,
+    max_vesting_beneficiaries: u32,
+    max_lock_duration: u64
+) -> Result<()> {
+    if max_vesting_beneficiaries == 0 {
+        return Err(LockError::InvalidConfig.into());
+    }
+
+    let config = &mut ctx.accounts.config;
+
+    config.bump = ctx.bumps.config;
+    config.admin = ctx.accounts.authority.key();
+    config.pending_admin = Pubkey::default();
+    config.paused = false;
+    config.max_vesting_beneficiaries = max_vesting_beneficiaries;
+    config.max_lock_duration = max_lock_duration;
+    config.treasury = ctx.accounts.authority.key();
+    config.flat_fee_lamports = 0;
+    config.fee_bps = 0;
+
+    emit!(ConfigUpdateEvent {
+        event_name: "initialize_config".to_string(),
+        admin: config.admin,
+        pending_admin: config.pending_admin,
+        paused: config.paused,
+        max_vesting_beneficiaries: config.max_vesting_beneficiaries,
+        max_lock_duration: config.max_lock_duration,
+        treasury: config.treasury,
+        flat_fee_lamports: config.flat_fee_lamports,
+        fee_bps: config.fee_bps,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface },
+};
+
+use crate::dex;
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+// Accounts
+#[derive(Accounts)]
+#[instruction(input:String)]
+pub struct LockDexPosition<'info> {
+    #[account(
+        init,
+        space = 8 + LpLock::INIT_SPACE,
+        seeds = [input.as_ref(), position_mint.key().as_ref(), owner.key().as_ref()],
+        bump,
+        payer = authority
+    )]
+    pub lock_pda: Box<Account<'info, LpLock>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub lock_ids: LockIds<'info>,
+
+    pub lock_index: LockIndex<'info>,
+
+    pub position_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    ///CHECK:safe
+    pub spl_mint_metadata_pda: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    ///CHECK:safe
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = position_mint,
+        associated_token::authority = lock_pda
+    )]
+    pub lock_pda_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub authority_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: checked against the supported clmm programs
+    pub dex_program: AccountInfo<'info>,
+
+    ///CHECK: owner and layout checked in the handler
+    pub position: AccountInfo<'info>,
+
+    ///CHECK: owner and layout checked in the handler
+    pub pool: AccountInfo<'info>,
+
+    ///CHECK: checked against config.treasury
+    #[account(mut, address = config.treasury @ LockError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+
+    pub fee_exemption: Option<Account<'info, FeeExemption>>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+//locks a raydium clmm or orca whirlpool position nft, the owner can still collect its fees
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, LockDexPosition<'info>>,
+    input: String,
+    lock_time: u64,
+    lock_name: String,
+    extra_data: String
+) -> Result<()> {
+    let lock_pda = &mut ctx.accounts.lock_pda;
+
+    if lock_time == 0 {
+        return Err(LockError::TimeZero.into());
+    }
+
+    let dex_program = ctx.accounts.dex_program.key();
+    let dex_name = dex::dex_name(&dex_program)?;
+
+    let position = dex::read_position(&dex_program, &ctx.accounts.position)?;
+    require_keys_eq!(position.position_mint, ctx.accounts.position_mint.key(), LockError::InvalidPositionAccount);
+    require_keys_eq!(position.pool, ctx.accounts.pool.key(), LockError::InvalidPoolAccount);
+    let (token_mint_a, token_mint_b) = dex::read_pool_mints(&dex_program, &ctx.accounts.pool)?;
+
+    let mint_policy = check_mint_extensions(&ctx.accounts.position_mint)?;
+    check_mint_metadata(&ctx.accounts.position_mint, &ctx.accounts.spl_mint_metadata_pda)?;
+
+    let clock = Clock::get();
+    let block_time = clock.unwrap().unix_timestamp as u64;
+
+    if lock_time <= block_time {
+        return Err(LockError::BeforeNow.into());
+    }
+
+    ctx.accounts.config.check_lock_duration(block_time, lock_time)?;
+
+    let lock_id = ctx.accounts.lock_ids.assign(
+        lock_pda.key(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    let header = &mut lock_pda.header;
+    header.bump = ctx.bumps.lock_pda;
+    header.lock_id = lock_id;
+    header.seed = input;
+    header.creator = ctx.accounts.owner.key();
+    header.authority = ctx.accounts.owner.key();
+    header.payer = ctx.accounts.authority.key();
+    header.spl_mint = token_mint_a;
+    header.vault_mint = ctx.accounts.position_mint.key();
+    header.seed_mint = ctx.accounts.position_mint.key();
+    header.spl_mint_metadata_pda = ctx.accounts.spl_mint_metadata_pda.key();
+    header.permanent_delegate = mint_policy.permanent_delegate;
+    header.lock_amount = 1;
+    header.start_time = block_time;
+    header.end_time = lock_time;
+    header.lock_name = lock_name;
+    header.extra_data = extra_data;
+
+    lock_pda.dex_name = dex_name.to_string();
+    lock_pda.token_mint_a = token_mint_a;
+    lock_pda.token_mint_b = token_mint_b;
+    lock_pda.dex_program = dex_program;
+    lock_pda.pool = ctx.accounts.pool.key();
+    lock_pda.position = ctx.accounts.position.key();
+    lock_pda.verified = true;
+
+    //the position is a single nft
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.authority_spl_ata.to_account_info(),
+        ctx.accounts.lock_pda_spl_ata.to_account_info(),
+        &ctx.accounts.position_mint,
+        ctx.accounts.token_program.to_account_info(),
+        1,
+        ctx.remaining_accounts
+    )?;
+
+    //a token fee on a single nft always rounds to zero, only the flat fee applies
+    let (fee_lamports, _) = lock_fees(
+        &ctx.accounts.config,
+        &ctx.accounts.fee_exemption,
+        &exempt_keys(ctx.accounts.position_mint.key(), ctx.accounts.authority.key(), Some((token_mint_a, token_mint_b))),
+        0
+    )?;
+
+    transfer_fee_lamports(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        fee_lamports
+    )?;
+
+    ctx.accounts.lock_index.record(
+        lock_pda.key(),
+        &lock_pda.header,
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    let header = &lock_pda.header;
+    emit!(CreateDexLockEvent {
+        event_name: "create_dex_lock".to_string(),
+        lock_pda: lock_pda.key(),
+        seed: header.seed.clone(),
+        lock_id: header.lock_id,
+        authority: header.authority,
+        spl_mint: header.spl_mint,
+        spl_mint_metadata_pda: header.spl_mint_metadata_pda,
+        lock_amount: header.lock_amount,
+        start_time: header.start_time,
+        end_time: header.end_time,
+        lock_name: header.lock_name.clone(),
+        extra_data: header.extra_data.clone(),
+        tge_bps: 0,
+        cycle: 0,
+        cycle_bps: 0,
+        dex_name: lock_pda.dex_name.clone(),
+        token_mint_a: lock_pda.token_mint_a,
+        token_mint_b: lock_pda.token_mint_b,
+        position_mint: header.vault_mint,
+        fee_lamports,
+        fee_amount: 0,
+        transfer_fee: 0,
+        pool: lock_pda.pool,
+        verified: lock_pda.verified,
+    });
+
+    Ok(())
+}
@@ -5,6 +5,7 @@ use anchor_spl::{
     token_interface::{ Mint, TokenAccount, TokenInterface },
 };
 
+use crate::dex;
 use crate::state::*;
 use crate::error::*;
 use crate::event::*;
@@ -14,15 +15,26 @@ use crate::utils::*;
 #[derive(Accounts)]
 #[instruction(input:String)]
 pub struct InitializeLockPda<'info> {
+    ///CHECK: created in the handler, a TokenLock or an LpLock when is_nft is set
     #[account(
-        init,
-        space = 8 + LockPda::LEN,
+        mut,
         seeds = [input.as_ref(), spl_mint.key().as_ref(), owner.key().as_ref()],
-        bump,
-        payer = authority
+        bump
     )]
-    pub lock_pda: Box<Account<'info, LockPda>>,
+    pub lock_pda: AccountInfo<'info>,
 
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub lock_ids: LockIds<'info>,
+
+    pub lock_index: LockIndex<'info>,
+
+    //the token, or the lp token or position nft when is_nft is set
     pub spl_mint: Box<InterfaceAccount<'info, Mint>>,
 
     ///CHECK:safe
@@ -44,6 +56,26 @@ pub struct InitializeLockPda<'info> {
 
     #[account(mut)]
     pub authority_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: optional, owner and layout checked in the handler
+    pub pool: Option<AccountInfo<'info>>,
+
+    ///CHECK: optional, the clmm position account when verifying a position nft
+    pub position: Option<AccountInfo<'info>>,
+
+    ///CHECK: checked against config.treasury
+    #[account(mut, address = config.treasury @ LockError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+
+    //only needed when a token fee is charged
+    #[account(
+        mut,
+        token::mint = spl_mint,
+        token::authority = treasury
+    )]
+    pub treasury_spl_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_exemption: Option<Account<'info, FeeExemption>>,
     pub token_program: Interface<'info, TokenInterface>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -51,8 +83,8 @@ pub struct InitializeLockPda<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn handler(
-    ctx: Context<InitializeLockPda>,
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeLockPda<'info>>,
     input: String,
     lock_amount: u64,
     lock_time: u64,
@@ -62,45 +94,33 @@ pub fn handler(
     project_token_mint: Pubkey,
     wsol_mint: Pubkey
 ) -> Result<()> {
-    let lock_pda = &mut ctx.accounts.lock_pda;
-
-    // if lock_amount == 0 {
-    //     return Err(LockError::AmountZero.into());
-    // }
-
-    let real_lock_amount;
-
-    if is_nft {
-        real_lock_amount = ctx.accounts.authority_spl_ata.amount;
-    } else {
-        real_lock_amount = lock_amount;
+    if !is_nft && lock_amount == 0 {
+        return Err(LockError::AmountZero.into());
     }
 
+    let mint_policy = check_mint_extensions(&ctx.accounts.spl_mint)?;
+
     if lock_time == 0 {
         return Err(LockError::TimeZero.into());
     }
 
-    if lock_pda.lock_amount > 0 {
-        return Err(LockError::AlreadyLocked.into());
-    }
+    //check spl_mint_metadata_pda is the metadata of the mint
+    check_mint_metadata(&ctx.accounts.spl_mint, &ctx.accounts.spl_mint_metadata_pda)?;
 
-    //check spl_mint fllows metaplex standard
-    // let expected = Metadata::find_pda(&ctx.accounts.spl_mint.key()).0;
-
-    // let metadata_pda = &ctx.accounts.spl_mint_metadata_pda;
-    // if metadata_pda.key() != expected {
-    //     return Err(LockError::NotValidToken.into());
-    // }
-
-    // if metadata_pda.data_len() == 0 {
-    //     return Err(LockError::NotValidToken.into());
-    // }
-
-    // if metadata_pda.data_len() != 0 {
-    //     if metadata_pda.owner != mpl_token_metadata::ID {
-    //         return Err(VendingMachineError::NotValidToken.into());
-    //     }
-    // }
+    //with a pool, the lp mint and pool mints are checked on chain, the lock is no longer manual
+    let verified_pool = match (is_nft, &ctx.accounts.pool) {
+        (true, Some(pool)) => {
+            let verified_pool = dex::verify_lp_mint(pool, ctx.accounts.position.as_ref(), &ctx.accounts.spl_mint.key())?;
+            if project_token_mint != verified_pool.token_mint_a && project_token_mint != verified_pool.token_mint_b {
+                return Err(LockError::InvalidPoolAccount.into());
+            }
+            Some(verified_pool)
+        }
+        (false, Some(_)) => {
+            return Err(LockError::InvalidPoolAccount.into());
+        }
+        (_, None) => None,
+    };
 
     let clock = Clock::get();
     let block_time = clock.unwrap().unix_timestamp as u64;
@@ -109,90 +129,196 @@ pub fn handler(
         return Err(LockError::BeforeNow.into());
     }
 
-    lock_pda.bump = ctx.bumps.lock_pda;
-    lock_pda.seed = input;
-    lock_pda.authority = ctx.accounts.owner.key();
-    lock_pda.spl_mint_metadata_pda = ctx.accounts.spl_mint_metadata_pda.key();
-    lock_pda.lock_amount = real_lock_amount;
-    lock_pda.start_time = block_time;
-    lock_pda.lock_name = lock_name;
-    lock_pda.extra_data = extra_data;
-
-    if is_nft {
-        //for the manual lp lock
-        lock_pda.spl_mint = project_token_mint;
-        lock_pda.dex_name = "manual lp".to_string();
-        if project_token_mint > wsol_mint {
-            lock_pda.token_mint_a = wsol_mint;
-            lock_pda.token_mint_b = project_token_mint;
-        }else{
-            lock_pda.token_mint_a = project_token_mint;
-            lock_pda.token_mint_b = wsol_mint; 
-        }
-        lock_pda.end_time = lock_time/1000;
-        lock_pda.position_mint = ctx.accounts.spl_mint.key();
+    ctx.accounts.config.check_lock_duration(block_time, lock_time)?;
+
+    //an lp lock takes the whole lp balance and the token fee comes out of it,
+    //a token lock pays the token fee on top of lock_amount
+    let (fee_lamports, fee_amount, deposit) = if is_nft {
+        let balance = ctx.accounts.authority_spl_ata.amount;
+        let (fee_lamports, fee_amount) = lock_fees(
+            &ctx.accounts.config,
+            &ctx.accounts.fee_exemption,
+            &exempt_keys(
+                ctx.accounts.spl_mint.key(),
+                ctx.accounts.authority.key(),
+                verified_pool.as_ref().map(|verified_pool| (verified_pool.token_mint_a, verified_pool.token_mint_b))
+            ),
+            balance
+        )?;
+        (fee_lamports, fee_amount, balance - fee_amount)
     } else {
-        //for the normal token lock
-        lock_pda.end_time = lock_time;
-        lock_pda.spl_mint = ctx.accounts.spl_mint.key();
-        lock_pda.dex_name = "manual".to_string();
+        let (fee_lamports, fee_amount) = lock_fees(
+            &ctx.accounts.config,
+            &ctx.accounts.fee_exemption,
+            &[ctx.accounts.spl_mint.key(), ctx.accounts.authority.key()],
+            lock_amount
+        )?;
+        (fee_lamports, fee_amount, lock_amount)
+    };
+
+    if deposit == 0 {
+        return Err(LockError::AmountZero.into());
     }
 
+    let lock_pda = &ctx.accounts.lock_pda;
+    let spl_mint = ctx.accounts.spl_mint.key();
+    let owner = ctx.accounts.owner.key();
+    let space = if is_nft { 8 + LpLock::INIT_SPACE } else { 8 + TokenLock::INIT_SPACE };
+    create_pda_account(
+        ctx.accounts.authority.to_account_info(),
+        lock_pda.clone(),
+        ctx.accounts.system_program.to_account_info(),
+        space,
+        &[input.as_ref(), spl_mint.as_ref(), owner.as_ref(), &[ctx.bumps.lock_pda]]
+    )?;
+
+    let lock_id = ctx.accounts.lock_ids.assign(
+        lock_pda.key(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    let vault_before = ctx.accounts.lock_pda_spl_ata.amount;
     transfer_from_user_to_pool_vault(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.authority_spl_ata.to_account_info(),
         ctx.accounts.lock_pda_spl_ata.to_account_info(),
-        ctx.accounts.spl_mint.to_account_info(),
+        &ctx.accounts.spl_mint,
         ctx.accounts.token_program.to_account_info(),
-        lock_pda.lock_amount,
-        ctx.accounts.spl_mint.decimals
+        deposit,
+        ctx.remaining_accounts
     )?;
 
-    lock_pda.lock_id = block_time as u32;
-
-    if is_nft {
-        //for the manual lp lock
-        emit!(CreateDexLockEvent {
-            event_name: "create_dex_lock".to_string(),
-            lock_pda: lock_pda.key(),
-            seed: lock_pda.seed.clone(),
-            lock_id: lock_pda.lock_id,
-            authority: lock_pda.authority,
-            spl_mint: lock_pda.spl_mint,
-            spl_mint_metadata_pda: lock_pda.spl_mint_metadata_pda,
-            lock_amount: lock_pda.lock_amount,
-            start_time: lock_pda.start_time,
-            end_time: lock_pda.end_time,
-            lock_name: lock_pda.lock_name.clone(),
-            extra_data: lock_pda.extra_data.clone(),
-            tge_bps: 0,
-            cycle: 0,
-            cycle_bps: 0,
-            dex_name: lock_pda.dex_name.clone(),
-            token_mint_a: lock_pda.token_mint_a,
-            token_mint_b: lock_pda.token_mint_b,
-            position_mint: lock_pda.position_mint,
-        });
+    //lock what actually arrived, transfer fee mints deliver less than the deposit
+    let received = received_amount(&mut ctx.accounts.lock_pda_spl_ata, vault_before)?;
+    let transfer_fee = deposit - received;
+
+    let header = LockHeader {
+        bump: ctx.bumps.lock_pda,
+        seed: input,
+        lock_id,
+        creator: owner,
+        authority: owner,
+        pending_authority: Pubkey::default(),
+        payer: ctx.accounts.authority.key(),
+        spl_mint: if is_nft { project_token_mint } else { spl_mint },
+        vault_mint: spl_mint,
+        seed_mint: spl_mint,
+        spl_mint_metadata_pda: ctx.accounts.spl_mint_metadata_pda.key(),
+        permanent_delegate: mint_policy.permanent_delegate,
+        lock_amount: received,
+        unlocked_amount: 0,
+        start_time: block_time,
+        end_time: lock_time,
+        lock_name,
+        extra_data,
+    };
+
+    let lp_lock = if is_nft {
+        let mut lp_lock = LpLock {
+            header: header.clone(),
+            dex_name: "manual lp".to_string(),
+            token_mint_a: project_token_mint.min(wsol_mint),
+            token_mint_b: project_token_mint.max(wsol_mint),
+            dex_program: Pubkey::default(),
+            pool: Pubkey::default(),
+            position: Pubkey::default(),
+            verified: false,
+            delegate: Pubkey::default(),
+        };
+        if let Some(verified_pool) = &verified_pool {
+            lp_lock.dex_name = verified_pool.dex_name.to_string();
+            lp_lock.token_mint_a = verified_pool.token_mint_a;
+            lp_lock.token_mint_b = verified_pool.token_mint_b;
+            verified_pool.record(&mut lp_lock);
+        }
+        save(lock_pda, &lp_lock)?;
+        Some(lp_lock)
     } else {
-        //for the normal token lock
-        emit!(CreateLockEvent {
-            event_name: "create_lock".to_string(),
-            lock_pda: lock_pda.key(),
-            seed: lock_pda.seed.clone(),
-            lock_id: lock_pda.lock_id,
-            authority: lock_pda.authority,
-            spl_mint: lock_pda.spl_mint,
-            spl_mint_metadata_pda: lock_pda.spl_mint_metadata_pda,
-            lock_amount: lock_pda.lock_amount,
-            start_time: lock_pda.start_time,
-            end_time: lock_pda.end_time,
-            lock_name: lock_pda.lock_name.clone(),
-            extra_data: lock_pda.extra_data.clone(),
-            tge_bps: 0,
-            cycle: 0,
-            cycle_bps: 0,
-            dex_name: lock_pda.dex_name.clone(),
-        });
+        save(lock_pda, &TokenLock { header: header.clone() })?;
+        None
+    };
+
+    transfer_fee_lamports(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        fee_lamports
+    )?;
+
+    if let Some(treasury_spl_ata) = fee_token_account(&ctx.accounts.treasury_spl_ata, fee_amount)? {
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.authority_spl_ata.to_account_info(),
+            treasury_spl_ata,
+            &ctx.accounts.spl_mint,
+            ctx.accounts.token_program.to_account_info(),
+            fee_amount,
+            ctx.remaining_accounts
+        )?;
+    }
+
+    ctx.accounts.lock_index.record(
+        lock_pda.key(),
+        &header,
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    match lp_lock {
+        Some(lp_lock) => {
+            //for the manual lp lock
+            emit!(CreateDexLockEvent {
+                event_name: "create_dex_lock".to_string(),
+                lock_pda: lock_pda.key(),
+                seed: header.seed.clone(),
+                lock_id: header.lock_id,
+                authority: header.authority,
+                spl_mint: header.spl_mint,
+                spl_mint_metadata_pda: header.spl_mint_metadata_pda,
+                lock_amount: header.lock_amount,
+                start_time: header.start_time,
+                end_time: header.end_time,
+                lock_name: header.lock_name.clone(),
+                extra_data: header.extra_data.clone(),
+                tge_bps: 0,
+                cycle: 0,
+                cycle_bps: 0,
+                dex_name: lp_lock.dex_name.clone(),
+                token_mint_a: lp_lock.token_mint_a,
+                token_mint_b: lp_lock.token_mint_b,
+                position_mint: header.vault_mint,
+                fee_lamports,
+                fee_amount,
+                transfer_fee,
+                pool: lp_lock.pool,
+                verified: lp_lock.verified,
+            });
+        }
+        None => {
+            //for the normal token lock
+            emit!(CreateLockEvent {
+                event_name: "create_lock".to_string(),
+                lock_pda: lock_pda.key(),
+                seed: header.seed.clone(),
+                lock_id: header.lock_id,
+                authority: header.authority,
+                spl_mint: header.spl_mint,
+                spl_mint_metadata_pda: header.spl_mint_metadata_pda,
+                lock_amount: header.lock_amount,
+                start_time: header.start_time,
+                end_time: header.end_time,
+                lock_name: header.lock_name.clone(),
+                extra_data: header.extra_data.clone(),
+                tge_bps: 0,
+                cycle: 0,
+                cycle_bps: 0,
+                dex_name: "manual".to_string(),
+                fee_lamports,
+                fee_amount,
+                transfer_fee,
+            });
+        }
     }
 
     Ok(())
@@ -16,12 +16,23 @@ use crate::utils::*;
 pub struct LockVesting<'info> {
     #[account(
         init,
-        space = 8 + LockPda::LEN,
+        space = 8 + VestingLock::INIT_SPACE,
         seeds = [input.as_ref(), spl_mint.key().as_ref(), authority.key().as_ref()],
         bump,
         payer = authority
     )]
-    pub lock_pda: Box<Account<'info, LockPda>>,
+    pub lock_pda: Box<Account<'info, VestingLock>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub lock_ids: LockIds<'info>,
+
+    pub lock_index: LockIndex<'info>,
 
     pub spl_mint: Box<InterfaceAccount<'info, Mint>>,
 
@@ -41,6 +52,20 @@ pub struct LockVesting<'info> {
 
     #[account(mut)]
     pub authority_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: checked against config.treasury
+    #[account(mut, address = config.treasury @ LockError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+
+    //only needed when a token fee is charged
+    #[account(
+        mut,
+        token::mint = spl_mint,
+        token::authority = treasury
+    )]
+    pub treasury_spl_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_exemption: Option<Account<'info, FeeExemption>>,
     pub token_program: Interface<'info, TokenInterface>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -48,15 +73,15 @@ pub struct LockVesting<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn handler(
-    ctx: Context<LockVesting>,
+//remaining accounts are the VestingBeneficiary pdas for user_list, in the same order,
+//followed by the transfer hook accounts of the mint
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, LockVesting<'info>>,
     input: String,
     lock_time: u64,
     lock_name: String,
     extra_data: String,
-    first_release: f64,
-    vesting_period: u64,
-    amount_per_vesting: f64,
+    schedule: VestingSchedule,
     user_list: Vec<Pubkey>,
     user_amount: Vec<u64>
 ) -> Result<()> {
@@ -66,27 +91,14 @@ pub fn handler(
         return Err(LockError::TimeZero.into());
     }
 
-    if lock_pda.lock_amount > 0 {
-        return Err(LockError::AlreadyLocked.into());
-    }
-
-    //check spl_mint fllows metaplex standard
-    // let expected = Metadata::find_pda(&ctx.accounts.spl_mint.key()).0;
-
-    // let metadata_pda = &ctx.accounts.spl_mint_metadata_pda;
-    // if metadata_pda.key() != expected {
-    //     return Err(LockError::NotValidToken.into());
-    // }
+    let mint_policy = check_mint_extensions(&ctx.accounts.spl_mint)?;
 
-    // if metadata_pda.data_len() == 0 {
-    //     return Err(LockError::NotValidToken.into());
-    // }
+    if user_list.len() > ctx.accounts.config.max_vesting_beneficiaries as usize {
+        return Err(LockError::TooManyBeneficiaries.into());
+    }
 
-    // if metadata_pda.data_len() != 0 {
-    //     if metadata_pda.owner != mpl_token_metadata::ID {
-    //         return Err(VendingMachineError::NotValidToken.into());
-    //     }
-    // }
+    //check spl_mint_metadata_pda is the metadata of the mint
+    check_mint_metadata(&ctx.accounts.spl_mint, &ctx.accounts.spl_mint_metadata_pda)?;
 
     let clock = Clock::get();
     let block_time = clock.unwrap().unix_timestamp as u64;
@@ -95,71 +107,135 @@ pub fn handler(
         return Err(LockError::BeforeNow.into());
     }
 
-    lock_pda.bump = ctx.bumps.lock_pda;
-    lock_pda.seed = input;
-    lock_pda.authority = ctx.accounts.authority.key();
-    lock_pda.spl_mint = ctx.accounts.spl_mint.key();
-    lock_pda.spl_mint_metadata_pda = ctx.accounts.spl_mint_metadata_pda.key();
-    lock_pda.start_time = block_time;
-    lock_pda.end_time = lock_time;
-    lock_pda.lock_name = lock_name;
-    lock_pda.extra_data = extra_data;
-    lock_pda.dex_name = "manual".to_string();
-    lock_pda.first_release = first_release;
-    lock_pda.vesting_period = vesting_period;
-    lock_pda.amount_per_vesting = amount_per_vesting;
-    lock_pda.user_list = user_list;
-    lock_pda.user_amount = user_amount;
-
-    let mut lock_amount=0;
-
-    for i in 0..lock_pda.user_amount.len() {
-        lock_amount += lock_pda.user_amount[i];
+    ctx.accounts.config.check_lock_duration(block_time, lock_time)?;
+
+    schedule.validate(lock_time)?;
+
+    let lock_id = ctx.accounts.lock_ids.assign(
+        lock_pda.key(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    let header = &mut lock_pda.header;
+    header.bump = ctx.bumps.lock_pda;
+    header.lock_id = lock_id;
+    header.seed = input;
+    header.creator = ctx.accounts.authority.key();
+    header.authority = ctx.accounts.authority.key();
+    header.payer = ctx.accounts.authority.key();
+    header.spl_mint = ctx.accounts.spl_mint.key();
+    header.vault_mint = ctx.accounts.spl_mint.key();
+    header.seed_mint = ctx.accounts.spl_mint.key();
+    header.spl_mint_metadata_pda = ctx.accounts.spl_mint_metadata_pda.key();
+    header.permanent_delegate = mint_policy.permanent_delegate;
+    header.start_time = block_time;
+    header.end_time = lock_time;
+    header.lock_name = lock_name;
+    header.extra_data = extra_data;
+
+    lock_pda.set_schedule(&schedule);
+
+    if ctx.remaining_accounts.len() < user_list.len() {
+        return Err(LockError::InvalidBeneficiaryAccount.into());
     }
+    let (beneficiary_accounts, hook_accounts) = ctx.remaining_accounts.split_at(user_list.len());
 
-    lock_pda.released_status =
-    vec![0u8; lock_pda.user_amount.len()];
-    lock_pda.pre_unlocked_time =
-    vec![0u64; lock_pda.user_amount.len()];
+    let lock_amount = create_beneficiaries(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        lock_pda.key(),
+        beneficiary_accounts,
+        &user_list,
+        &user_amount
+    )?;
 
     if lock_amount == 0 {
         return Err(LockError::AmountZero.into());
     }
 
-    lock_pda.lock_amount = lock_amount;
+    lock_pda.header.lock_amount = lock_amount;
+    lock_pda.beneficiary_count = user_list.len() as u32;
+    //a one-shot vesting lock is claimable right away
+    lock_pda.finalized = true;
 
+    //the vault must hold every beneficiary amount, so the sender covers the transfer fee
+    let gross_amount = amount_with_transfer_fee(&ctx.accounts.spl_mint, lock_amount)?;
+    let vault_before = ctx.accounts.lock_pda_spl_ata.amount;
     transfer_from_user_to_pool_vault(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.authority_spl_ata.to_account_info(),
         ctx.accounts.lock_pda_spl_ata.to_account_info(),
-        ctx.accounts.spl_mint.to_account_info(),
+        &ctx.accounts.spl_mint,
         ctx.accounts.token_program.to_account_info(),
-        lock_amount,
-        ctx.accounts.spl_mint.decimals
+        gross_amount,
+        hook_accounts
+    )?;
+
+    let received = received_amount(&mut ctx.accounts.lock_pda_spl_ata, vault_before)?;
+    if received < lock_amount {
+        return Err(LockError::LowBalance.into());
+    }
+    let transfer_fee = gross_amount - received;
+
+    let (fee_lamports, fee_amount) = lock_fees(
+        &ctx.accounts.config,
+        &ctx.accounts.fee_exemption,
+        &[ctx.accounts.spl_mint.key(), ctx.accounts.authority.key()],
+        lock_amount
+    )?;
+
+    transfer_fee_lamports(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        fee_lamports
+    )?;
+
+    if let Some(treasury_spl_ata) = fee_token_account(&ctx.accounts.treasury_spl_ata, fee_amount)? {
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.authority_spl_ata.to_account_info(),
+            treasury_spl_ata,
+            &ctx.accounts.spl_mint,
+            ctx.accounts.token_program.to_account_info(),
+            fee_amount,
+            hook_accounts
+        )?;
+    }
+
+    ctx.accounts.lock_index.record(
+        lock_pda.key(),
+        &lock_pda.header,
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
     )?;
 
+    let header = &lock_pda.header;
     emit!(LockVestingEvent {
         event_name: "lock_vesting".to_string(),
         lock_pda: lock_pda.key(),
-        seed: lock_pda.seed.clone(),
-        lock_id: lock_pda.lock_id,
-        authority: lock_pda.authority,
-        spl_mint: lock_pda.spl_mint,
-        spl_mint_metadata_pda: lock_pda.spl_mint_metadata_pda,
-        lock_amount: lock_pda.lock_amount,
-        start_time: lock_pda.start_time,
-        end_time: lock_pda.end_time,
-        lock_name: lock_pda.lock_name.clone(),
-        extra_data: lock_pda.extra_data.clone(),
-        first_release: lock_pda.first_release,
-        vesting_period: lock_pda.vesting_period,
-        amount_per_vesting: lock_pda.amount_per_vesting,
-        user_list: lock_pda.user_list.clone(),
-        user_amount: lock_pda.user_amount.clone(),
-        tge_bps: 0,
-        cycle: 0,
-        cycle_bps: 0,
-        dex_name: lock_pda.dex_name.clone(),
+        seed: header.seed.clone(),
+        lock_id: header.lock_id,
+        authority: header.authority,
+        spl_mint: header.spl_mint,
+        spl_mint_metadata_pda: header.spl_mint_metadata_pda,
+        lock_amount: header.lock_amount,
+        start_time: header.start_time,
+        end_time: header.end_time,
+        lock_name: header.lock_name.clone(),
+        extra_data: header.extra_data.clone(),
+        vesting_type: lock_pda.vesting_type,
+        vesting_end_time: lock_pda.vesting_end_time,
+        user_list,
+        user_amount,
+        tge_bps: lock_pda.tge_bps,
+        cycle: lock_pda.vesting_period,
+        cycle_bps: lock_pda.cycle_bps,
+        dex_name: "manual".to_string(),
+        fee_lamports,
+        fee_amount,
+        transfer_fee,
     });
 
     Ok(())
@@ -0,0 +1,342 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::token_interface::Mint;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct MigrateLockAccount<'info> {
+    ///CHECK: a LockPda, owner, discriminator and address checked in the handler
+    #[account(mut)]
+    pub lock_pda: AccountInfo<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub lock_ids: LockIds<'info>,
+
+    pub lock_index: LockIndex<'info>,
+
+    //the mint held in the lock vault
+    pub vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    ///CHECK: checked against the lock payer, receives the rent freed by the smaller account
+    #[account(mut)]
+    pub lock_payer: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+//rewrites a LockPda as a TokenLock, LpLock or VestingLock at the same address, so the vault
+//and the pda seeds stay the same. vesting locks take one VestingBeneficiary pda per user
+//as remaining accounts, in the order users first appear in user_list.
+//lp locks made by create_lock_pda were derived from the spl_mint passed at creation, which the
+//lock did not store, callers pass it as `seed_mint`. other locks were derived from their vault mint.
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateLockAccount<'info>>,
+    seed_mint: Option<Pubkey>
+) -> Result<()> {
+    let lock_pda = &ctx.accounts.lock_pda;
+    require_keys_eq!(*lock_pda.owner, crate::ID, LockError::InvalidLockAccount);
+
+    //fails on anything but a LockPda, so a lock migrates once
+    let mut legacy = LockPda::try_deserialize(&mut &lock_pda.try_borrow_data()?[..])?;
+    legacy.migrate_time_unit();
+
+    let seed_mint = seed_mint.unwrap_or(legacy.vault_mint());
+    check_lock_address(lock_pda.key(), &legacy, seed_mint)?;
+    require_keys_eq!(ctx.accounts.vault_mint.key(), legacy.vault_mint(), LockError::SplMintError);
+
+    //legacy locks did not record a payer, the rent goes back to their authority
+    require_keys_eq!(ctx.accounts.lock_payer.key(), legacy.authority, LockError::AuthorizationErr);
+
+    let payer = ctx.accounts.payer.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    let lock_id = ctx.accounts.lock_ids.assign(lock_pda.key(), payer.clone(), system_program.clone())?;
+
+    let mut header = legacy_header(&legacy, seed_mint, lock_id, has_permanent_delegate(&ctx.accounts.vault_mint)?);
+
+    let mut beneficiary_count = 0;
+    let (lock_kind, space) = if legacy.position_mint != Pubkey::default() {
+        let lp_lock = LpLock {
+            header: header.clone(),
+            dex_name: truncated(&legacy.dex_name, 32),
+            token_mint_a: legacy.token_mint_a,
+            token_mint_b: legacy.token_mint_b,
+            dex_program: Pubkey::default(),
+            pool: Pubkey::default(),
+            position: Pubkey::default(),
+            verified: false,
+            delegate: Pubkey::default(),
+        };
+        rewrite(lock_pda, &lp_lock, 8 + LpLock::INIT_SPACE)?;
+        ("lp", 8 + LpLock::INIT_SPACE)
+    } else if !legacy.user_list.is_empty() {
+        let mut beneficiaries = legacy_beneficiaries(&legacy, lock_pda.key())?;
+        create_beneficiaries(&mut beneficiaries, ctx.remaining_accounts, &payer, &system_program)?;
+        beneficiary_count = beneficiaries.len() as u32;
+        move_released_amount(&mut header, &beneficiaries)?;
+
+        let vesting_lock = VestingLock {
+            header: header.clone(),
+            vesting_type: VestingType::Tranche,
+            tge_bps: legacy.tge_bps(),
+            cycle_bps: legacy.cycle_bps(),
+            vesting_period: legacy.vesting_period,
+            vesting_end_time: 0,
+            beneficiary_count,
+            finalized: true,
+            merkle_root: [0u8; 32],
+        };
+        rewrite(lock_pda, &vesting_lock, 8 + VestingLock::INIT_SPACE)?;
+        ("vesting", 8 + VestingLock::INIT_SPACE)
+    } else {
+        let token_lock = TokenLock { header: header.clone() };
+        rewrite(lock_pda, &token_lock, 8 + TokenLock::INIT_SPACE)?;
+        ("token", 8 + TokenLock::INIT_SPACE)
+    };
+
+    //the new account is a fraction of LockPda::LEN, the freed rent goes back to the lock payer
+    let excess_lamports = lock_pda.lamports().saturating_sub(Rent::get()?.minimum_balance(space));
+    **lock_pda.try_borrow_mut_lamports()? -= excess_lamports;
+    **ctx.accounts.lock_payer.try_borrow_mut_lamports()? += excess_lamports;
+
+    ctx.accounts.lock_index.record(lock_pda.key(), &header, payer, system_program)?;
+
+    emit!(MigrateLockAccountEvent {
+        event_name: "migrate_lock_account".to_string(),
+        seed: header.seed.clone(),
+        lock_pda: lock_pda.key(),
+        lock_id,
+        lock_kind: lock_kind.to_string(),
+        authority: header.authority,
+        spl_mint: header.spl_mint,
+        lock_amount: header.lock_amount,
+        end_time: header.end_time,
+        beneficiary_count,
+    });
+
+    Ok(())
+}
+
+//the lock must sit at the address the legacy program derived it at
+fn check_lock_address(lock_pda: Pubkey, legacy: &LockPda, seed_mint: Pubkey) -> Result<()> {
+    let expected = Pubkey::create_program_address(
+        &[legacy.seed.as_ref(), seed_mint.as_ref(), legacy.authority.as_ref(), &[legacy.bump]],
+        &crate::ID
+    ).map_err(|_| LockError::InvalidLockAccount)?;
+    require_keys_eq!(lock_pda, expected, LockError::InvalidLockAccount);
+    Ok(())
+}
+
+//legacy locks had no separate creator or payer, both are the authority the pda was derived from
+fn legacy_header(legacy: &LockPda, seed_mint: Pubkey, lock_id: u64, permanent_delegate: bool) -> LockHeader {
+    LockHeader {
+        bump: legacy.bump,
+        seed: legacy.seed.clone(),
+        lock_id,
+        creator: legacy.authority,
+        authority: legacy.authority,
+        pending_authority: Pubkey::default(),
+        payer: legacy.authority,
+        spl_mint: legacy.spl_mint,
+        vault_mint: legacy.vault_mint(),
+        seed_mint,
+        spl_mint_metadata_pda: legacy.spl_mint_metadata_pda,
+        permanent_delegate,
+        lock_amount: legacy.lock_amount,
+        unlocked_amount: 0,
+        start_time: legacy.start_time,
+        end_time: legacy.end_time,
+        lock_name: truncated(&legacy.lock_name, 100),
+        extra_data: truncated(&legacy.extra_data, 100),
+    }
+}
+
+//legacy vesting locks kept lock_amount at the total, what was claimed is now unlocked
+fn move_released_amount(header: &mut LockHeader, beneficiaries: &[VestingBeneficiary]) -> Result<()> {
+    header.unlocked_amount = beneficiaries
+        .iter()
+        .try_fold(0u64, |total, beneficiary| total.checked_add(beneficiary.released_amount))
+        .ok_or(LockError::MathOverflow)?;
+    header.lock_amount = header.lock_amount.saturating_sub(header.unlocked_amount);
+    Ok(())
+}
+
+//shrinks the lock account to `space` and writes `lock` with its discriminator
+fn rewrite<T: AccountSerialize>(lock_pda: &AccountInfo, lock: &T, space: usize) -> Result<()> {
+    lock_pda.realloc(space, false)?;
+    save(lock_pda, lock)
+}
+
+//one VestingBeneficiary per user with what the user was owed and already claimed,
+//a user listed twice gets one account with both entries added up
+fn legacy_beneficiaries(legacy: &LockPda, vesting_lock: Pubkey) -> Result<Vec<VestingBeneficiary>> {
+    let mut beneficiaries: Vec<VestingBeneficiary> = Vec::new();
+
+    for (user, amount) in legacy.user_list.iter().zip(&legacy.user_amount) {
+        let released_amount = legacy.released_amount(*amount);
+        match beneficiaries.iter_mut().find(|beneficiary| beneficiary.beneficiary == *user) {
+            Some(beneficiary) => {
+                beneficiary.amount = beneficiary.amount.checked_add(*amount).ok_or(LockError::MathOverflow)?;
+                beneficiary.released_amount = beneficiary.released_amount
+                    .checked_add(released_amount)
+                    .ok_or(LockError::MathOverflow)?;
+            }
+            None =>
+                beneficiaries.push(VestingBeneficiary {
+                    bump: 0,
+                    vesting_lock,
+                    beneficiary: *user,
+                    amount: *amount,
+                    released_amount,
+                    last_claim_time: 0,
+                }),
+        }
+    }
+
+    Ok(beneficiaries)
+}
+
+//creates the beneficiary pdas, passed in the order users first appear in user_list
+fn create_beneficiaries<'info>(
+    beneficiaries: &mut [VestingBeneficiary],
+    beneficiary_accounts: &'info [AccountInfo<'info>],
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>
+) -> Result<()> {
+    if beneficiary_accounts.len() != beneficiaries.len() {
+        return Err(LockError::InvalidBeneficiaryAccount.into());
+    }
+
+    for (beneficiary_info, beneficiary) in beneficiary_accounts.iter().zip(beneficiaries.iter_mut()) {
+        let vesting_lock = beneficiary.vesting_lock;
+        let (expected, bump) = Pubkey::find_program_address(
+            &[VESTING_BENEFICIARY_SEED, vesting_lock.as_ref(), beneficiary.beneficiary.as_ref()],
+            &crate::ID
+        );
+        require_keys_eq!(beneficiary_info.key(), expected, LockError::InvalidBeneficiaryAccount);
+
+        create_pda_account(
+            payer.clone(),
+            beneficiary_info.clone(),
+            system_program.clone(),
+            8 + VestingBeneficiary::INIT_SPACE,
+            &[VESTING_BENEFICIARY_SEED, vesting_lock.as_ref(), beneficiary.beneficiary.as_ref(), &[bump]]
+        )?;
+        beneficiary.bump = bump;
+        save(beneficiary_info, beneficiary)?;
+    }
+
+    Ok(())
+}
+
+//legacy strings had no per-field limit, cut them to the new max_len on a char boundary
+fn truncated(value: &str, max_len: usize) -> String {
+    let mut end = value.len().min(max_len);
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    value[..end].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::tests::{ baseline_lock, baseline_lock_data };
+
+    //moves `legacy` to the address the legacy program derived from `seed_mint`
+    fn derive(legacy: &mut LockPda, seed_mint: Pubkey) -> Pubkey {
+        let (address, bump) = Pubkey::find_program_address(
+            &[legacy.seed.as_ref(), seed_mint.as_ref(), legacy.authority.as_ref()],
+            &crate::ID
+        );
+        legacy.bump = bump;
+        address
+    }
+
+    #[test]
+    fn finds_token_locks_by_their_vault_mint() {
+        let mut legacy = baseline_lock();
+        legacy.user_list.clear();
+        let spl_mint = legacy.spl_mint;
+        let address = derive(&mut legacy, spl_mint);
+
+        assert!(check_lock_address(address, &legacy, legacy.vault_mint()).is_ok());
+        assert!(check_lock_address(address, &legacy, Pubkey::new_unique()).is_err());
+
+        let header = legacy_header(&legacy, legacy.vault_mint(), 7, false);
+        assert_eq!(header.creator, legacy.authority);
+        assert_eq!(header.authority, legacy.authority);
+        assert_eq!(header.payer, legacy.authority);
+        assert_eq!(header.seed_mint, legacy.spl_mint);
+        assert_eq!(header.vault_mint, legacy.spl_mint);
+        assert_eq!(header.lock_id, 7);
+    }
+
+    #[test]
+    fn finds_create_lock_pda_locks_by_the_passed_seed_mint() {
+        let mut legacy = baseline_lock();
+        legacy.user_list.clear();
+        legacy.position_mint = Pubkey::new_unique();
+        let seed_mint = Pubkey::new_unique();
+        let address = derive(&mut legacy, seed_mint);
+
+        assert!(check_lock_address(address, &legacy, legacy.vault_mint()).is_err());
+        assert!(check_lock_address(address, &legacy, seed_mint).is_ok());
+
+        let header = legacy_header(&legacy, seed_mint, 7, false);
+        assert_eq!(header.seed_mint, seed_mint);
+        assert_eq!(header.vault_mint, legacy.position_mint);
+    }
+
+    #[test]
+    fn migrates_a_baseline_vesting_lock() {
+        let mut legacy = baseline_lock();
+        legacy.end_time = 1_800_000_000_000;
+        let spl_mint = legacy.spl_mint;
+        let address = derive(&mut legacy, spl_mint);
+
+        //read back from the bytes the legacy program wrote
+        let mut stored = LockPda::try_deserialize(&mut &baseline_lock_data(&legacy)[..]).unwrap();
+        assert!(stored.migrate_time_unit());
+        check_lock_address(address, &stored, stored.vault_mint()).unwrap();
+
+        let beneficiaries = legacy_beneficiaries(&stored, address).unwrap();
+        assert_eq!(beneficiaries.len(), 2);
+        assert_eq!(beneficiaries[0].beneficiary, legacy.user_list[0]);
+        assert_eq!(beneficiaries[0].amount, 500);
+        assert_eq!(beneficiaries[0].released_amount, 150);
+        //the user listed twice holds both entries
+        assert_eq!(beneficiaries[1].beneficiary, legacy.user_list[1]);
+        assert_eq!(beneficiaries[1].amount, 500);
+        assert_eq!(beneficiaries[1].released_amount, 150);
+        assert!(beneficiaries.iter().all(|beneficiary| beneficiary.vesting_lock == address));
+
+        let mut header = legacy_header(&stored, stored.vault_mint(), 1, false);
+        move_released_amount(&mut header, &beneficiaries).unwrap();
+        assert_eq!(header.end_time, 1_800_000_000);
+        assert_eq!(header.unlocked_amount, 300);
+        assert_eq!(header.lock_amount, 700);
+        assert_eq!(header.creator, legacy.authority);
+    }
+
+    #[test]
+    fn truncates_on_char_boundaries() {
+        assert_eq!(truncated("abc", 2), "ab");
+        assert_eq!(truncated("aé", 2), "a");
+        assert_eq!(truncated("abc", 100), "abc");
+    }
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct MigrateLockTime<'info> {
+    ///CHECK: a LockPda in the baseline layout, owner and discriminator checked in the handler
+    #[account(mut)]
+    pub lock_pda: AccountInfo<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+}
+
+pub(crate) fn handler(ctx: Context<MigrateLockTime>) -> Result<()> {
+    let lock_pda = &ctx.accounts.lock_pda;
+
+    let legacy = match migrate_end_time(lock_pda)? {
+        Some(legacy) => legacy,
+        None => {
+            return Ok(());
+        }
+    };
+
+    emit!(MigrateLockTimeEvent {
+        event_name: "migrate_lock_time".to_string(),
+        seed: legacy.seed.clone(),
+        lock_pda: lock_pda.key(),
+        start_time: legacy.start_time,
+        end_time: legacy.end_time,
+    });
+
+    Ok(())
+}
+
+//reads the lock from the raw account data and writes it back with end_time in seconds,
+//returns None when the times were already in seconds
+fn migrate_end_time(lock_pda: &AccountInfo) -> Result<Option<LockPda>> {
+    require_keys_eq!(*lock_pda.owner, crate::ID, LockError::InvalidLockAccount);
+
+    let mut legacy = LockPda::try_deserialize(&mut &lock_pda.try_borrow_data()?[..])?;
+    if !legacy.migrate_time_unit() {
+        return Ok(None);
+    }
+
+    //the fields keep their size, the rewritten lock fits the space it was created with
+    save(lock_pda, &legacy)?;
+    Ok(Some(legacy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::tests::{ baseline_lock, baseline_lock_data };
+
+    #[test]
+    fn migrates_a_baseline_millisecond_lock() {
+        let key = Pubkey::new_unique();
+        let owner = crate::ID;
+        let mut lamports = 0;
+        let mut lock = baseline_lock();
+        lock.end_time = 1_900_000_000_000;
+        let mut data = baseline_lock_data(&lock);
+        let data_len = data.len();
+        let lock_pda = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+
+        let migrated = migrate_end_time(&lock_pda).unwrap().unwrap();
+        assert_eq!(migrated.end_time, 1_900_000_000);
+        assert_eq!(lock_pda.data_len(), data_len);
+
+        //the rest of the account reads back unchanged
+        let stored = LockPda::try_deserialize(&mut &lock_pda.try_borrow_data().unwrap()[..]).unwrap();
+        assert_eq!(stored.end_time, 1_900_000_000);
+        assert_eq!(stored.start_time, lock.start_time);
+        assert_eq!(stored.user_list, lock.user_list);
+        assert_eq!(stored.user_amount, lock.user_amount);
+        assert_eq!(stored.claimed_token_percent, lock.claimed_token_percent);
+
+        //a second call finds the time in seconds
+        assert!(migrate_end_time(&lock_pda).unwrap().is_none());
+    }
+
+    #[test]
+    fn keeps_a_baseline_second_lock() {
+        let key = Pubkey::new_unique();
+        let owner = crate::ID;
+        let mut lamports = 0;
+        let lock = baseline_lock();
+        let mut data = baseline_lock_data(&lock);
+        let lock_pda = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+
+        assert!(migrate_end_time(&lock_pda).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_accounts_of_other_programs() {
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = baseline_lock_data(&baseline_lock());
+        let lock_pda = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+
+        assert!(migrate_end_time(&lock_pda).is_err());
+    }
+}
@@ -2,14 +2,58 @@ pub mod lock_token;
 pub mod unlock_token;
 pub mod create_lock_pda;
 pub mod unlock_lp;
+pub mod lock_dex_position;
+pub mod collect_position_fees;
 pub mod lock_vesting;
 pub mod unlock_vesting;
+pub mod create_vesting_pool;
+pub mod add_beneficiaries;
+pub mod finalize_vesting_pool;
+pub mod set_merkle_root;
+pub mod unlock_merkle_vesting;
 pub mod extend_lock_time;
+pub mod increase_lock_amount;
+pub mod close_lock;
+pub mod migrate_lock_time;
+pub mod migrate_lock_account;
+pub mod transfer_lock_ownership;
+pub mod accept_lock_ownership;
+pub mod set_lp_delegate;
+pub mod initialize_config;
+pub mod update_config;
+pub mod set_paused;
+pub mod propose_admin;
+pub mod accept_admin;
+pub mod update_fee_config;
+pub mod add_fee_exemption;
+pub mod remove_fee_exemption;
 
 pub use lock_token::*;
 pub use unlock_token::*;
 pub use create_lock_pda::*;
 pub use unlock_lp::*;
+pub use lock_dex_position::*;
+pub use collect_position_fees::*;
 pub use lock_vesting::*;
 pub use unlock_vesting::*;
+pub use create_vesting_pool::*;
+pub use add_beneficiaries::*;
+pub use finalize_vesting_pool::*;
+pub use set_merkle_root::*;
+pub use unlock_merkle_vesting::*;
 pub use extend_lock_time::*;
+pub use increase_lock_amount::*;
+pub use close_lock::*;
+pub use migrate_lock_time::*;
+pub use migrate_lock_account::*;
+pub use transfer_lock_ownership::*;
+pub use accept_lock_ownership::*;
+pub use set_lp_delegate::*;
+pub use initialize_config::*;
+pub use update_config::*;
+pub use set_paused::*;
+pub use propose_admin::*;
+pub use accept_admin::*;
+pub use update_fee_config::*;
+pub use add_fee_exemption::*;
+pub use remove_fee_exemption::*;
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ LockError::AuthorizationErr
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub admin: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    //Pubkey::default() cancels a pending transfer
+    config.pending_admin = new_admin;
+
+    emit!(ConfigUpdateEvent {
+        event_name: "propose_admin".to_string(),
+        admin: config.admin,
+        pending_admin: config.pending_admin,
+        paused: config.paused,
+        max_vesting_beneficiaries: config.max_vesting_beneficiaries,
+        max_lock_duration: config.max_lock_duration,
+        treasury: config.treasury,
+        flat_fee_lamports: config.flat_fee_lamports,
+        fee_bps: config.fee_bps,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct RemoveFeeExemption<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ LockError::AuthorizationErr
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    #[account(
+        mut,
+        seeds = [FEE_EXEMPTION_SEED, fee_exemption.target.as_ref()],
+        bump = fee_exemption.bump,
+        close = admin
+    )]
+    pub fee_exemption: Box<Account<'info, FeeExemption>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<RemoveFeeExemption>) -> Result<()> {
+    emit!(FeeExemptionEvent {
+        event_name: "remove_fee_exemption".to_string(),
+        target: ctx.accounts.fee_exemption.target,
+        exempt: false,
+    });
+
+    Ok(())
+}
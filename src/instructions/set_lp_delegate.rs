@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct SetLpDelegate<'info> {
+    #[account(
+        mut,
+        constraint = lp_lock.header.authority == authority.key() @ LockError::AuthorizationErr
+    )]
+    pub lp_lock: Box<Account<'info, LpLock>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub authority: Signer<'info>,
+}
+
+//the delegate can unlock the lp once it ends, the tokens still go to the authority.
+//Pubkey::default() removes the delegate
+pub(crate) fn handler(ctx: Context<SetLpDelegate>, delegate: Pubkey) -> Result<()> {
+    let lp_lock = &mut ctx.accounts.lp_lock;
+    lp_lock.delegate = delegate;
+
+    emit!(SetLpDelegateEvent {
+        event_name: "set_lp_delegate".to_string(),
+        seed: lp_lock.header.seed.clone(),
+        lock_pda: lp_lock.key(),
+        lock_id: lp_lock.header.lock_id,
+        authority: lp_lock.header.authority,
+        delegate,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface },
+};
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct SetMerkleRoot<'info> {
+    #[account(
+        mut,
+        seeds = [lock_pda.header.seed.as_ref(), spl_mint.key().as_ref(), lock_pda.header.creator.as_ref()],
+        bump = lock_pda.header.bump,
+        constraint = lock_pda.header.authority == authority.key() @ LockError::AuthorizationErr,
+        constraint = !lock_pda.finalized @ LockError::VestingPoolFinalized,
+        constraint = lock_pda.merkle_root == [0u8; 32] @ LockError::MerkleRootAlreadySet,
+        constraint = lock_pda.beneficiary_count == 0 @ LockError::MerkleRootAlreadySet
+    )]
+    pub lock_pda: Box<Account<'info, VestingLock>>,
+
+    ///CHECK: checked in the handler, MintLockStats at [MINT_STATS_SEED, spl_mint]
+    #[account(mut)]
+    pub mint_stats: AccountInfo<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub spl_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = spl_mint,
+        token::authority = lock_pda
+    )]
+    pub lock_pda_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub authority_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: checked against config.treasury
+    #[account(mut, address = config.treasury @ LockError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+
+    //only needed when a token fee is charged
+    #[account(
+        mut,
+        token::mint = spl_mint,
+        token::authority = treasury
+    )]
+    pub treasury_spl_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_exemption: Option<Account<'info, FeeExemption>>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+//commits the pool to a merkle root over (beneficiary, amount) leaves and deposits
+//their total, beneficiaries then claim with unlock_merkle_vesting and a proof
+//total_amount is not checked against the leaves, claims past it fail with LowBalance
+pub(crate) fn handler<'info>(ctx: Context<'_, '_, '_, 'info, SetMerkleRoot<'info>>, merkle_root: [u8; 32], total_amount: u64) -> Result<()> {
+    let lock_pda = &mut ctx.accounts.lock_pda;
+
+    if merkle_root == [0u8; 32] {
+        return Err(LockError::InvalidMerkleProof.into());
+    }
+
+    if total_amount == 0 {
+        return Err(LockError::AmountZero.into());
+    }
+
+    lock_pda.merkle_root = merkle_root;
+    lock_pda.header.lock_amount = total_amount;
+    add_locked_amount(&ctx.accounts.mint_stats, &lock_pda.header, total_amount)?;
+
+    //the vault must hold every beneficiary amount, so the sender covers the transfer fee
+    let gross_amount = amount_with_transfer_fee(&ctx.accounts.spl_mint, total_amount)?;
+    let vault_before = ctx.accounts.lock_pda_spl_ata.amount;
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.authority_spl_ata.to_account_info(),
+        ctx.accounts.lock_pda_spl_ata.to_account_info(),
+        &ctx.accounts.spl_mint,
+        ctx.accounts.token_program.to_account_info(),
+        gross_amount,
+        ctx.remaining_accounts
+    )?;
+
+    let received = received_amount(&mut ctx.accounts.lock_pda_spl_ata, vault_before)?;
+    if received < total_amount {
+        return Err(LockError::LowBalance.into());
+    }
+    let transfer_fee = gross_amount - received;
+
+    //the flat fee was paid when the pool was created
+    let (_, fee_amount) = lock_fees(
+        &ctx.accounts.config,
+        &ctx.accounts.fee_exemption,
+        &[ctx.accounts.spl_mint.key(), ctx.accounts.authority.key()],
+        total_amount
+    )?;
+
+    if let Some(treasury_spl_ata) = fee_token_account(&ctx.accounts.treasury_spl_ata, fee_amount)? {
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.authority_spl_ata.to_account_info(),
+            treasury_spl_ata,
+            &ctx.accounts.spl_mint,
+            ctx.accounts.token_program.to_account_info(),
+            fee_amount,
+            ctx.remaining_accounts
+        )?;
+    }
+
+    emit!(SetMerkleRootEvent {
+        event_name: "set_merkle_root".to_string(),
+        seed: lock_pda.header.seed.clone(),
+        lock_pda: lock_pda.key(),
+        lock_id: lock_pda.header.lock_id,
+        merkle_root,
+        lock_amount: total_amount,
+        fee_amount,
+        transfer_fee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ LockError::AuthorizationErr
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub admin: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    config.paused = paused;
+
+    emit!(ConfigUpdateEvent {
+        event_name: "set_paused".to_string(),
+        admin: config.admin,
+        pending_admin: config.pending_admin,
+        paused: config.paused,
+        max_vesting_beneficiaries: config.max_vesting_beneficiaries,
+        max_lock_duration: config.max_lock_duration,
+        treasury: config.treasury,
+        flat_fee_lamports: config.flat_fee_lamports,
+        fee_bps: config.fee_bps,
+    });
+
+    Ok(())
+}
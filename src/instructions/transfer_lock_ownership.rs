@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct TransferLockOwnership<'info> {
+    #[account(mut)]
+    pub token_lock: Option<Box<Account<'info, TokenLock>>>,
+
+    #[account(mut)]
+    pub lp_lock: Option<Box<Account<'info, LpLock>>>,
+
+    #[account(mut)]
+    pub vesting_lock: Option<Box<Account<'info, VestingLock>>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<TransferLockOwnership>, new_authority: Pubkey) -> Result<()> {
+    let (lock_pda, header) = lock_header(
+        &mut ctx.accounts.token_lock,
+        &mut ctx.accounts.lp_lock,
+        &mut ctx.accounts.vesting_lock
+    )?;
+
+    if header.authority != ctx.accounts.authority.key() {
+        return Err(LockError::AuthorizationErr.into());
+    }
+
+    //Pubkey::default() cancels a pending transfer
+    header.pending_authority = new_authority;
+
+    emit!(ProposeLockOwnershipEvent {
+        event_name: "propose_lock_ownership".to_string(),
+        seed: header.seed.clone(),
+        lock_pda: lock_pda.key(),
+        lock_id: header.lock_id,
+        authority: header.authority,
+        pending_authority: header.pending_authority,
+    });
+
+    Ok(())
+}
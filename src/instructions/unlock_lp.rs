@@ -1,26 +1,45 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{ token::{ self, Mint, Token, TokenAccount }, associated_token::AssociatedToken };
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface },
+};
 
 use crate::state::*;
 use crate::error::*;
 use crate::event::*;
+use crate::utils::*;
 
 #[derive(Accounts)]
 #[instruction(input:String)]
 pub struct UnlockLp<'info> {
     #[account(
         mut,
-        seeds = [input.as_ref(), spl_mint.key().as_ref(), authority.key().as_ref()],
-        bump
+        seeds = [input.as_ref(), lock_pda.header.seed_mint.as_ref(), lock_pda.header.creator.as_ref()],
+        bump = lock_pda.header.bump
     )]
-    pub lock_pda: Box<Account<'info, LockPda>>,
+    pub lock_pda: Box<Account<'info, LpLock>>,
 
-    #[account(mut)]
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    //the owner or the delegate they approved
+    #[account(
+        mut,
+        constraint = authority.key() == lock_pda.header.authority ||
+            authority.key() == lock_pda.delegate @ LockError::AuthorizationErr
+    )]
     pub authority: Signer<'info>,
 
-    pub spl_mint: Account<'info, Mint>,
+    ///CHECK: checked against lock_pda.header.authority, receives the tokens
+    #[account(address = lock_pda.header.authority @ LockError::AuthorizationErr)]
+    pub owner: AccountInfo<'info>,
 
-    pub position_mint: Account<'info, Mint>,
+    #[account(address = lock_pda.header.vault_mint @ LockError::SplMintError)]
+    pub position_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         init_if_needed,
@@ -28,84 +47,85 @@ pub struct UnlockLp<'info> {
         associated_token::mint = position_mint,
         associated_token::authority = lock_pda
     )]
-    pub lock_pda_spl_ata: Box<Account<'info, TokenAccount>>,
+    pub lock_pda_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = authority,
         associated_token::mint = position_mint,
-        associated_token::authority = authority
+        associated_token::authority = owner
     )]
-    pub authority_spl_ata: Box<Account<'info, TokenAccount>>,
+    pub owner_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn handler(ctx: Context<UnlockLp>, input: String) -> Result<()> {
+pub(crate) fn handler<'info>(ctx: Context<'_, '_, '_, 'info, UnlockLp<'info>>, input: String) -> Result<()> {
     let lock_pda = &mut ctx.accounts.lock_pda;
 
     let clock = Clock::get();
     let block_time = clock.unwrap().unix_timestamp as u64;
 
-    if block_time < lock_pda.end_time {
+    if block_time < lock_pda.header.end_time {
         return Err(LockError::NotUnlockTime.into());
     }
 
-    if lock_pda.lock_amount == 0 {
+    if lock_pda.header.lock_amount == 0 {
         return Err(LockError::AlreadyUnlocked.into());
     }
 
-    // require_keys_eq!(
-    //     ctx.accounts.authority.key(),
-    //     lock_pda.authority,
-    //     LockError::AuthorizationErr
-    // );
-   
-    require_keys_eq!(ctx.accounts.position_mint.key(), lock_pda.position_mint, LockError::SplMintError);
-
-    let authority = ctx.accounts.authority.key();
-    let spl_mint = ctx.accounts.spl_mint.key();
-    let lock_bump = lock_pda.bump;
-    let seeds = &[input.as_ref(), spl_mint.as_ref(), authority.as_ref(), &[lock_bump]];
+    let creator = lock_pda.header.creator;
+    let seed_mint = lock_pda.header.seed_mint;
+    let lock_bump = lock_pda.header.bump;
+    let seeds = &[input.as_ref(), seed_mint.as_ref(), creator.as_ref(), &[lock_bump]];
     let signer = &[&seeds[..]];
 
-    let transfer_token_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
+    transfer_from_pool_vault_to_user(
+        TransferAccounts {
             authority: lock_pda.to_account_info(),
             from: ctx.accounts.lock_pda_spl_ata.to_account_info(),
-            to: ctx.accounts.authority_spl_ata.to_account_info(),
+            to: ctx.accounts.owner_spl_ata.to_account_info(),
+            mint: &ctx.accounts.position_mint,
+            token_program: ctx.accounts.token_program.to_account_info(),
+            hook_accounts: ctx.remaining_accounts,
         },
+        lock_pda.header.lock_amount,
         signer
-    );
-    token::transfer(transfer_token_ctx, lock_pda.lock_amount)?;
+    )?;
 
-    lock_pda.lock_amount = 0;
+    let unlock_amount = lock_pda.header.lock_amount;
+    let header = &mut lock_pda.header;
+    header.unlocked_amount = header.unlocked_amount
+        .checked_add(unlock_amount)
+        .ok_or(LockError::MathOverflow)?;
+    header.lock_amount = 0;
 
+    let header = &lock_pda.header;
     emit!(UnlockLpEvent {
         event_name: "unlock_lp_event".to_string(),
         lock_pda: lock_pda.key(),
-        seed: lock_pda.seed.clone(),
-        lock_id: lock_pda.lock_id,
-        authority: ctx.accounts.authority.key(),
-        spl_mint: lock_pda.spl_mint,
-        spl_mint_metadata_pda: lock_pda.spl_mint_metadata_pda,
-        lock_amount: lock_pda.lock_amount,
-        start_time: lock_pda.start_time,
-        end_time: lock_pda.end_time,
-        lock_name: lock_pda.lock_name.clone(),
-        extra_data: lock_pda.extra_data.clone(),
+        seed: header.seed.clone(),
+        lock_id: header.lock_id,
+        authority: header.authority,
+        unlocked_by: ctx.accounts.authority.key(),
+        spl_mint: header.spl_mint,
+        spl_mint_metadata_pda: header.spl_mint_metadata_pda,
+        lock_amount: unlock_amount,
+        start_time: header.start_time,
+        end_time: header.end_time,
+        lock_name: header.lock_name.clone(),
+        extra_data: header.extra_data.clone(),
         tge_bps: 0,
         cycle: 0,
         cycle_bps: 0,
         dex_name: lock_pda.dex_name.clone(),
         token_mint_a: lock_pda.token_mint_a,
         token_mint_b: lock_pda.token_mint_b,
-        position_mint: lock_pda.position_mint,
+        position_mint: header.vault_mint,
     });
 
     Ok(())
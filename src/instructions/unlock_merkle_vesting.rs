@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface },
+};
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+#[instruction(input:String)]
+pub struct UnlockMerkleVesting<'info> {
+    #[account(
+        mut,
+        seeds = [input.as_ref(),spl_mint.key().as_ref(), lock_pda.header.creator.as_ref()],
+        bump = lock_pda.header.bump,
+        constraint = lock_pda.finalized @ LockError::VestingPoolNotFinalized,
+        constraint = lock_pda.merkle_root != [0u8; 32] @ LockError::NotMerkleVesting
+    )]
+    pub lock_pda: Box<Account<'info, VestingLock>>,
+
+    ///CHECK: checked in the handler, MintLockStats at [MINT_STATS_SEED, spl_mint]
+    #[account(mut)]
+    pub mint_stats: AccountInfo<'info>,
+
+    //created on the first claim
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + VestingBeneficiary::INIT_SPACE,
+        seeds = [VESTING_BENEFICIARY_SEED, lock_pda.key().as_ref(), authority.key().as_ref()],
+        bump,
+    )]
+    pub beneficiary: Box<Account<'info, VestingBeneficiary>>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub spl_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    ///CHECK:safe
+    pub spl_mint_metadata_pda: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = spl_mint,
+        token::authority = lock_pda
+    )]
+    pub lock_pda_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = spl_mint,
+        associated_token::authority = authority
+    )]
+    pub authority_spl_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, UnlockMerkleVesting<'info>>,
+    input: String,
+    amount: u64,
+    proof: Vec<[u8; 32]>
+) -> Result<()> {
+    let lock_pda = &mut ctx.accounts.lock_pda;
+
+    let clock = Clock::get();
+    let block_time = clock.unwrap().unix_timestamp as u64;
+
+    //each beneficiary tracks its own claimed amount and last claim time
+    let beneficiary = &mut ctx.accounts.beneficiary;
+
+    let leaf = merkle_leaf(&ctx.accounts.authority.key(), amount);
+    if !verify_merkle_proof(&proof, &lock_pda.merkle_root, leaf) {
+        return Err(LockError::InvalidMerkleProof.into());
+    }
+
+    if beneficiary.vesting_lock == Pubkey::default() {
+        beneficiary.bump = ctx.bumps.beneficiary;
+        beneficiary.vesting_lock = lock_pda.key();
+        beneficiary.beneficiary = ctx.accounts.authority.key();
+        beneficiary.amount = amount;
+        lock_pda.beneficiary_count = lock_pda.beneficiary_count
+            .checked_add(1)
+            .ok_or(LockError::MathOverflow)?;
+    } else if beneficiary.amount != amount {
+        return Err(LockError::InvalidMerkleProof.into());
+    }
+
+    let unlock_amount = release_vested(lock_pda, beneficiary, block_time)?;
+    remove_locked_amount(&ctx.accounts.mint_stats, &lock_pda.header, unlock_amount)?;
+
+    let header = &lock_pda.header;
+    require_keys_eq!(ctx.accounts.spl_mint.key(), header.vault_mint, LockError::SplMintError);
+
+    let creator = header.creator;
+    let vault_mint = header.vault_mint;
+    let lock_bump = header.bump;
+    let seeds = &[input.as_ref(), vault_mint.as_ref(), creator.as_ref(), &[lock_bump]];
+    let signer = &[&seeds[..]];
+
+    //the beneficiary receives unlock_amount - transfer_fee for transfer fee mints
+    let transfer_fee = transfer_fee_amount(&ctx.accounts.spl_mint, unlock_amount)?;
+
+    transfer_from_pool_vault_to_user(
+        TransferAccounts {
+            authority: lock_pda.to_account_info(),
+            from: ctx.accounts.lock_pda_spl_ata.to_account_info(),
+            to: ctx.accounts.authority_spl_ata.to_account_info(),
+            mint: &ctx.accounts.spl_mint,
+            token_program: ctx.accounts.token_program.to_account_info(),
+            hook_accounts: ctx.remaining_accounts,
+        },
+        unlock_amount,
+        signer
+    )?;
+
+    let header = &lock_pda.header;
+    emit!(UnlockVestingEvent {
+        event_name: "unlock_merkle_vesting".to_string(),
+        lock_pda: lock_pda.key(),
+        seed: header.seed.clone(),
+        lock_id: header.lock_id,
+        authority: header.authority,
+        spl_mint: header.spl_mint,
+        spl_mint_metadata_pda: header.spl_mint_metadata_pda,
+        unlock_amount,
+        start_time: header.start_time,
+        end_time: header.end_time,
+        lock_name: header.lock_name.clone(),
+        extra_data: header.extra_data.clone(),
+        vesting_type: lock_pda.vesting_type,
+        vesting_end_time: lock_pda.vesting_end_time,
+        beneficiary: beneficiary.beneficiary,
+        beneficiary_amount: beneficiary.amount,
+        released_amount: beneficiary.released_amount,
+        tge_bps: lock_pda.tge_bps,
+        cycle: lock_pda.vesting_period,
+        cycle_bps: lock_pda.cycle_bps,
+        unlocker: ctx.accounts.authority.key(),
+        transfer_fee,
+    });
+
+    Ok(())
+}
@@ -15,10 +15,21 @@ use crate::utils::*;
 pub struct UnlockPda<'info> {
     #[account(
         mut,
-        seeds = [input.as_ref(),spl_mint.key().as_ref(), authority.key().as_ref()],
-        bump,
+        seeds = [input.as_ref(),spl_mint.key().as_ref(), lock_pda.header.creator.as_ref()],
+        bump = lock_pda.header.bump,
     )]
-    pub lock_pda: Box<Account<'info, LockPda>>,
+    pub lock_pda: Box<Account<'info, TokenLock>>,
+
+    ///CHECK: checked in the handler, MintLockStats at [MINT_STATS_SEED, spl_mint]
+    #[account(mut)]
+    pub mint_stats: AccountInfo<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.paused @ LockError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
 
     pub spl_mint:  Box<InterfaceAccount<'info, Mint>>,
 
@@ -50,62 +61,82 @@ pub struct UnlockPda<'info> {
 }
 
 
-pub fn handler(ctx: Context<UnlockPda>, input: String) -> Result<()> {
+pub(crate) fn handler<'info>(ctx: Context<'_, '_, '_, 'info, UnlockPda<'info>>, input: String, amount: u64) -> Result<()> {
     let lock_pda = &mut ctx.accounts.lock_pda;
 
     let clock = Clock::get();
     let block_time = clock.unwrap().unix_timestamp as u64;
 
-    if block_time*1000 < lock_pda.end_time {
+    if block_time < lock_pda.header.end_time {
         return Err(LockError::NotUnlockTime.into());
     }
 
-    if lock_pda.lock_amount == 0 {
+    if lock_pda.header.lock_amount == 0 {
         return Err(LockError::AlreadyUnlocked.into());
     }
 
+    if amount == 0 {
+        return Err(LockError::AmountZero.into());
+    }
+
+    if amount > lock_pda.header.lock_amount {
+        return Err(LockError::LowBalance.into());
+    }
+
     require_keys_eq!(
         ctx.accounts.authority.key(),
-        lock_pda.authority,
+        lock_pda.header.authority,
         LockError::AuthorizationErr
     );
-    require_keys_eq!(ctx.accounts.spl_mint.key(), lock_pda.spl_mint, LockError::SplMintError);
 
-    let authority = lock_pda.authority;
-    let spl_mint = lock_pda.spl_mint;
-    let lock_bump = lock_pda.bump;
-    let seeds = &[input.as_ref(), spl_mint.as_ref(), authority.as_ref(), &[lock_bump]];
+    let creator = lock_pda.header.creator;
+    let spl_mint = lock_pda.header.vault_mint;
+    let lock_bump = lock_pda.header.bump;
+    let seeds = &[input.as_ref(), spl_mint.as_ref(), creator.as_ref(), &[lock_bump]];
     let signer = &[&seeds[..]];
 
+    //the user receives amount - transfer_fee for transfer fee mints
+    let transfer_fee = transfer_fee_amount(&ctx.accounts.spl_mint, amount)?;
+
     transfer_from_pool_vault_to_user(
-        lock_pda.to_account_info(),
-        ctx.accounts.lock_pda_spl_ata.to_account_info(),
-        ctx.accounts.authority_spl_ata.to_account_info(),
-        ctx.accounts.spl_mint.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-        lock_pda.lock_amount,
-        ctx.accounts.spl_mint.decimals,
+        TransferAccounts {
+            authority: lock_pda.to_account_info(),
+            from: ctx.accounts.lock_pda_spl_ata.to_account_info(),
+            to: ctx.accounts.authority_spl_ata.to_account_info(),
+            mint: &ctx.accounts.spl_mint,
+            token_program: ctx.accounts.token_program.to_account_info(),
+            hook_accounts: ctx.remaining_accounts,
+        },
+        amount,
         signer
     )?;
 
-    lock_pda.lock_amount = 0;
+    let header = &mut lock_pda.header;
+    header.lock_amount -= amount;
+    header.unlocked_amount = header.unlocked_amount
+        .checked_add(amount)
+        .ok_or(LockError::MathOverflow)?;
+    remove_locked_amount(&ctx.accounts.mint_stats, header, amount)?;
 
+    let header = &lock_pda.header;
     emit!(UnlockEvent {
         event_name: "unlock".to_string(),
         lock_pda: lock_pda.key(),
-        seed: lock_pda.seed.clone(),
-        lock_id: lock_pda.lock_id,
-        authority: lock_pda.authority,
-        spl_mint: lock_pda.spl_mint,
-        spl_mint_metadata_pda: lock_pda.spl_mint_metadata_pda,
-        unlock_amount: lock_pda.lock_amount,
-        start_time: lock_pda.start_time,
-        end_time: lock_pda.end_time,
-        lock_name: lock_pda.lock_name.clone(),
-        extra_data: lock_pda.extra_data.clone(),
+        seed: header.seed.clone(),
+        lock_id: header.lock_id,
+        authority: header.authority,
+        spl_mint: header.spl_mint,
+        spl_mint_metadata_pda: header.spl_mint_metadata_pda,
+        unlock_amount: amount,
+        remaining_amount: header.lock_amount,
+        start_time: header.start_time,
+        end_time: header.end_time,
+        lock_name: header.lock_name.clone(),
+        extra_data: header.extra_data.clone(),
         tge_bps: 0,
         cycle: 0,
         cycle_bps: 0,
+        transfer_fee,
     });
 
     Ok(())
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::event::*;
+
+// Accounts
+#[derive(Accounts)]
+pub struct UpdateFeeConfig<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ LockError::AuthorizationErr
+    )]
+    pub config: Box<Account<'info, ProgramConfig>>,
+
+    pub admin: Signer<'info>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<UpdateFeeConfig>,
+    treasury: Pubkey,
+    flat_fee_lamports: u64,
+    fee_bps: u16
+) -> Result<()> {
+    if treasury == Pubkey::default() {
+        return Err(LockError::InvalidTreasury.into());
+    }
+
+    if fee_bps > MAX_FEE_BPS {
+        return Err(LockError::InvalidConfig.into());
+    }
+
+    let config = &mut ctx.accounts.config;
+
+    config.treasury = treasury;
+    config.flat_fee_lamports = flat_fee_lamports;
+    config.fee_bps = fee_bps;
+
+    emit!(ConfigUpdateEvent {
+        event_name: "update_fee_config".to_string(),
+        admin: config.admin,
+        pending_admin: config.pending_admin,
+        paused: config.paused,
+        max_vesting_beneficiaries: config.max_vesting_beneficiaries,
+        max_lock_duration: config.max_lock_duration,
+        treasury: config.treasury,
+        flat_fee_lamports: config.flat_fee_lamports,
+        fee_bps: config.fee_bps,
+    });
+
+    Ok(())
+}
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
 use instructions::*;
+use state::*;
 
 declare_id!("8gQmKKkHXHKkM3YKmRmFXdwzaQKoMcQg4YWW4TQGqvCR");
 
+pub mod dex;
 pub mod error;
 pub mod event;
 pub mod instructions;
@@ -13,8 +15,8 @@ pub mod utils;
 mod gempad_solana_lock {
     use super::*;
 
-    pub fn lock_token(
-        ctx: Context<InitializeLockPda>,
+    pub fn lock_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeLockPda<'info>>,
         input: String,
         lock_amount: u64,
         lock_time: u64,
@@ -24,7 +26,7 @@ mod gempad_solana_lock {
         project_token_mint: Pubkey,
         wsol_mint: Pubkey,
     ) -> Result<()> {
-        return instructions::lock_token::handler(
+        instructions::lock_token::handler(
             ctx,
             input,
             lock_amount,
@@ -34,15 +36,15 @@ mod gempad_solana_lock {
             is_nft,
             project_token_mint,
             wsol_mint,
-        );
+        )
     }
 
-    pub fn unlock_token(ctx: Context<UnlockPda>, input: String) -> Result<()> {
-        return instructions::unlock_token::handler(ctx, input);
+    pub fn unlock_token<'info>(ctx: Context<'_, '_, '_, 'info, UnlockPda<'info>>, input: String, amount: u64) -> Result<()> {
+        instructions::unlock_token::handler(ctx, input, amount)
     }
 
-    pub fn create_lock_pda(
-        ctx: Context<LockTest>,
+    pub fn create_lock_pda<'info>(
+        ctx: Context<'_, '_, '_, 'info, LockTest<'info>>,
         input: String,
         lock_amount: u64,
         lock_time: u64,
@@ -52,7 +54,7 @@ mod gempad_solana_lock {
         token_mint_a: Pubkey,
         token_mint_b: Pubkey,
     ) -> Result<()> {
-        return instructions::create_lock_pda::handler(
+        instructions::create_lock_pda::handler(
             ctx,
             input,
             lock_amount,
@@ -62,45 +64,191 @@ mod gempad_solana_lock {
             dex_name,
             token_mint_a,
             token_mint_b,
-        );
+        )
     }
 
-    pub fn unlock_lp(ctx: Context<UnlockLp>, input: String) -> Result<()> {
-        return instructions::unlock_lp::handler(ctx, input);
+    pub fn unlock_lp<'info>(ctx: Context<'_, '_, '_, 'info, UnlockLp<'info>>, input: String) -> Result<()> {
+        instructions::unlock_lp::handler(ctx, input)
     }
 
-    pub fn lock_vesting(
-        ctx: Context<LockVesting>,
+    pub fn lock_dex_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, LockDexPosition<'info>>,
         input: String,
         lock_time: u64,
         lock_name: String,
         extra_data: String,
-        first_release: f64,
-        vesting_period: u64,
-        amount_per_vesting: f64,
+    ) -> Result<()> {
+        instructions::lock_dex_position::handler(ctx, input, lock_time, lock_name, extra_data)
+    }
+
+    pub fn collect_position_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, CollectPositionFees<'info>>,
+        input: String,
+        reward_index: Option<u8>,
+    ) -> Result<()> {
+        instructions::collect_position_fees::handler(ctx, input, reward_index)
+    }
+
+    pub fn lock_vesting<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LockVesting<'info>>,
+        input: String,
+        lock_time: u64,
+        lock_name: String,
+        extra_data: String,
+        schedule: VestingSchedule,
         user_list: Vec<Pubkey>,
         user_amount: Vec<u64>,
     ) -> Result<()> {
-        return instructions::lock_vesting::handler(
+        instructions::lock_vesting::handler(
             ctx,
             input,
             lock_time,
             lock_name,
             extra_data,
-            first_release,
-            vesting_period,
-            amount_per_vesting,
+            schedule,
             user_list,
             user_amount,
-        );
+        )
     }
 
-    pub fn unlock_vesting(ctx: Context<UnlockVesting>, input: String) -> Result<()> {
-        return instructions::unlock_vesting::handler(ctx, input);
+    pub fn unlock_vesting<'info>(ctx: Context<'_, '_, '_, 'info, UnlockVesting<'info>>, input: String) -> Result<()> {
+        instructions::unlock_vesting::handler(ctx, input)
+    }
+
+    pub fn create_vesting_pool(
+        ctx: Context<CreateVestingPool>,
+        input: String,
+        lock_time: u64,
+        lock_name: String,
+        extra_data: String,
+        schedule: VestingSchedule,
+    ) -> Result<()> {
+        instructions::create_vesting_pool::handler(ctx, input, lock_time, lock_name, extra_data, schedule)
+    }
+
+    pub fn add_beneficiaries<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddBeneficiaries<'info>>,
+        user_list: Vec<Pubkey>,
+        user_amount: Vec<u64>,
+    ) -> Result<()> {
+        instructions::add_beneficiaries::handler(ctx, user_list, user_amount)
+    }
+
+    pub fn finalize_vesting_pool(ctx: Context<FinalizeVestingPool>) -> Result<()> {
+        instructions::finalize_vesting_pool::handler(ctx)
+    }
+
+    pub fn set_merkle_root<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetMerkleRoot<'info>>,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+    ) -> Result<()> {
+        instructions::set_merkle_root::handler(ctx, merkle_root, total_amount)
+    }
+
+    pub fn unlock_merkle_vesting<'info>(
+        ctx: Context<'_, '_, '_, 'info, UnlockMerkleVesting<'info>>,
+        input: String,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::unlock_merkle_vesting::handler(ctx, input, amount, proof)
     }
 
     pub fn extend_lock_time(ctx: Context<ExtendLockTime>, lock_time: u64) -> Result<()> {
-        return instructions::extend_lock_time::handler(ctx, lock_time);
+        instructions::extend_lock_time::handler(ctx, lock_time)
+    }
+
+    pub fn increase_lock_amount<'info>(ctx: Context<'_, '_, '_, 'info, IncreaseLockAmount<'info>>, amount: u64) -> Result<()> {
+        instructions::increase_lock_amount::handler(ctx, amount)
+    }
+
+    pub fn close_lock<'info>(ctx: Context<'_, '_, 'info, 'info, CloseLock<'info>>) -> Result<()> {
+        instructions::close_lock::handler(ctx)
+    }
+
+    pub fn migrate_lock_time(ctx: Context<MigrateLockTime>) -> Result<()> {
+        instructions::migrate_lock_time::handler(ctx)
+    }
+
+    pub fn migrate_lock_account<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateLockAccount<'info>>,
+        seed_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::migrate_lock_account::handler(ctx, seed_mint)
+    }
+
+    pub fn transfer_lock_ownership(
+        ctx: Context<TransferLockOwnership>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer_lock_ownership::handler(ctx, new_authority)
+    }
+
+    pub fn accept_lock_ownership(ctx: Context<AcceptLockOwnership>) -> Result<()> {
+        instructions::accept_lock_ownership::handler(ctx)
+    }
+
+    pub fn set_lp_delegate(ctx: Context<SetLpDelegate>, delegate: Pubkey) -> Result<()> {
+        instructions::set_lp_delegate::handler(ctx, delegate)
+    }
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        max_vesting_beneficiaries: u32,
+        max_lock_duration: u64,
+    ) -> Result<()> {
+        instructions::initialize_config::handler(
+            ctx,
+            max_vesting_beneficiaries,
+            max_lock_duration,
+        )
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        max_vesting_beneficiaries: u32,
+        max_lock_duration: u64,
+    ) -> Result<()> {
+        instructions::update_config::handler(
+            ctx,
+            max_vesting_beneficiaries,
+            max_lock_duration,
+        )
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::set_paused::handler(ctx, paused)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin::handler(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::handler(ctx)
+    }
+
+    pub fn update_fee_config(
+        ctx: Context<UpdateFeeConfig>,
+        treasury: Pubkey,
+        flat_fee_lamports: u64,
+        fee_bps: u16,
+    ) -> Result<()> {
+        instructions::update_fee_config::handler(
+            ctx,
+            treasury,
+            flat_fee_lamports,
+            fee_bps,
+        )
+    }
+
+    pub fn add_fee_exemption(ctx: Context<AddFeeExemption>, target: Pubkey) -> Result<()> {
+        instructions::add_fee_exemption::handler(ctx, target)
+    }
+
+    pub fn remove_fee_exemption(ctx: Context<RemoveFeeExemption>) -> Result<()> {
+        instructions::remove_fee_exemption::handler(ctx)
     }
 
 }
@@ -1,5 +1,107 @@
 use anchor_lang::prelude::*;
 
+use crate::error::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum VestingType {
+    //tge release at end_time, then cycle_bps every vesting_period days
+    Tranche,
+    //tge release at end_time, then linear per second until vesting_end_time
+    Linear,
+}
+
+//vesting schedule taken by lock_vesting and create_vesting_pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct VestingSchedule {
+    pub tge_bps: u16,
+    pub vesting_period: u64,
+    pub cycle_bps: u16,
+    pub vesting_type: VestingType,
+    pub vesting_end_time: u64,
+}
+
+//fields shared by every lock kind.
+//the lock pda is derived from [seed, seed_mint, creator] for all of them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct LockHeader {
+    pub bump: u8,
+    #[max_len(32)]
+    pub seed: String,
+    pub lock_id: u64, //assigned from LockCounter, starts at 1
+    pub creator: Pubkey, //key used in the pda seeds, authority is the current owner
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub payer: Pubkey, //receives the rent when the lock is closed
+    pub spl_mint: Pubkey,
+    pub vault_mint: Pubkey, //mint held in the lock vault, lp locks hold the position mint
+    pub seed_mint: Pubkey, //mint in the pda seeds, the vault mint except for create_lock_pda locks
+    pub spl_mint_metadata_pda: Pubkey,
+    pub permanent_delegate: bool, //warning, the mint delegate can move tokens out of the vault
+    pub lock_amount: u64, //still locked
+    pub unlocked_amount: u64, //withdrawn so far
+    pub start_time: u64,
+    pub end_time: u64,
+    #[max_len(100)]
+    pub lock_name: String,
+    #[max_len(100)]
+    pub extra_data: String,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TokenLock {
+    pub header: LockHeader,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LpLock {
+    pub header: LockHeader,
+    #[max_len(32)]
+    pub dex_name: String,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub dex_program: Pubkey, //set for clmm position locks, default otherwise
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub verified: bool, //lp mint checked against pool on chain
+    pub delegate: Pubkey, //may unlock to the authority, default when none
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VestingLock {
+    pub header: LockHeader,
+    pub vesting_type: VestingType,
+    pub tge_bps: u16,
+    pub cycle_bps: u16,
+    pub vesting_period: u64, //days between tranches
+    pub vesting_end_time: u64, //end of the linear release
+    pub beneficiary_count: u32,
+    pub finalized: bool, //pools take no new beneficiaries and can be claimed once finalized
+    pub merkle_root: [u8; 32], //root over (beneficiary, amount) leaves, zero when beneficiaries are added on-chain
+}
+
+//one per vesting user, derived from [VESTING_BENEFICIARY_SEED, vesting_lock, beneficiary]
+#[account]
+#[derive(InitSpace)]
+pub struct VestingBeneficiary {
+    pub bump: u8,
+    pub vesting_lock: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub released_amount: u64,
+    pub last_claim_time: u64,
+}
+
+pub const VESTING_BENEFICIARY_SEED: &[u8] = b"vesting_beneficiary";
+
+//lock times above this value can only be millisecond timestamps
+pub const MILLISECOND_TIME_THRESHOLD: u64 = 100_000_000_000;
+
+//the single account type every lock used before TokenLock, LpLock and VestingLock, in the
+//layout those locks were written with. no new LockPda is created, existing ones are read
+//by migrate_lock_time and rewritten by migrate_lock_account.
 #[account]
 pub struct LockPda {
     pub bump: u8, //1
@@ -54,7 +156,259 @@ impl LockPda {
         4 +
         8 * 100 +
         4 +
-        1 * 100 +
+        100 +
         4 +
         8 * 100;
+
+    //mint held in the lock vault, lp locks hold the position mint
+    pub fn vault_mint(&self) -> Pubkey {
+        if self.position_mint == Pubkey::default() {
+            self.spl_mint
+        } else {
+            self.position_mint
+        }
+    }
+
+    //converts a millisecond end_time to seconds, returns false when it was already in seconds
+    pub fn migrate_time_unit(&mut self) -> bool {
+        //start_time always came from the clock, only end_time could be in milliseconds
+        if self.end_time < MILLISECOND_TIME_THRESHOLD {
+            return false;
+        }
+
+        self.end_time /= 1000;
+        true
+    }
+
+    //first_release as basis points
+    pub fn tge_bps(&self) -> u16 {
+        percent_to_bps(self.first_release)
+    }
+
+    //amount_per_vesting as basis points
+    pub fn cycle_bps(&self) -> u16 {
+        percent_to_bps(self.amount_per_vesting)
+    }
+
+    //what a user of `user_amount` already claimed. the percentage was tracked once for the
+    //whole lock, so every user counts as having claimed it.
+    pub fn released_amount(&self, user_amount: u64) -> u64 {
+        let claimed_percent = self.claimed_token_percent.min(100) as u128;
+        ((user_amount as u128) * claimed_percent / 100) as u64
+    }
+}
+
+//a percentage with up to two decimals, as the vesting schedule used to store it
+fn percent_to_bps(percent: f64) -> u16 {
+    (percent * 100.0).round().clamp(0.0, 10_000.0) as u16
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    //a vesting lock with two users, the second of them listed twice
+    pub fn baseline_lock() -> LockPda {
+        let user = Pubkey::new_unique();
+        LockPda {
+            bump: 254,
+            seed: "baseline".to_string(),
+            lock_id: 1_700_000_000,
+            authority: Pubkey::new_unique(),
+            spl_mint: Pubkey::new_unique(),
+            spl_mint_metadata_pda: Pubkey::new_unique(),
+            lock_amount: 1_000,
+            start_time: 1_700_000_000,
+            end_time: 1_800_000_000,
+            lock_name: "lock".to_string(),
+            extra_data: "data".to_string(),
+            dex_name: "manual".to_string(),
+            token_mint_a: Pubkey::default(),
+            token_mint_b: Pubkey::default(),
+            position_mint: Pubkey::default(),
+            first_release: 12.5,
+            vesting_period: 30,
+            amount_per_vesting: 17.5,
+            user_list: vec![Pubkey::new_unique(), user, user],
+            user_amount: vec![500, 300, 200],
+            released_status: vec![0, 0, 0],
+            pre_unlocked_time: vec![0, 0, 0],
+            claimed_token_percent: 30,
+        }
+    }
+
+    //the account data the baseline program wrote for `lock`, encoded field by field
+    pub fn baseline_lock_data(lock: &LockPda) -> Vec<u8> {
+        fn string(data: &mut Vec<u8>, value: &str) {
+            data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            data.extend_from_slice(value.as_bytes());
+        }
+
+        let mut data = LockPda::discriminator().to_vec();
+        data.push(lock.bump);
+        string(&mut data, &lock.seed);
+        data.extend_from_slice(&lock.lock_id.to_le_bytes());
+        data.extend_from_slice(lock.authority.as_ref());
+        data.extend_from_slice(lock.spl_mint.as_ref());
+        data.extend_from_slice(lock.spl_mint_metadata_pda.as_ref());
+        data.extend_from_slice(&lock.lock_amount.to_le_bytes());
+        data.extend_from_slice(&lock.start_time.to_le_bytes());
+        data.extend_from_slice(&lock.end_time.to_le_bytes());
+        string(&mut data, &lock.lock_name);
+        string(&mut data, &lock.extra_data);
+        string(&mut data, &lock.dex_name);
+        data.extend_from_slice(lock.token_mint_a.as_ref());
+        data.extend_from_slice(lock.token_mint_b.as_ref());
+        data.extend_from_slice(lock.position_mint.as_ref());
+        data.extend_from_slice(&lock.first_release.to_le_bytes());
+        data.extend_from_slice(&lock.vesting_period.to_le_bytes());
+        data.extend_from_slice(&lock.amount_per_vesting.to_le_bytes());
+        data.extend_from_slice(&(lock.user_list.len() as u32).to_le_bytes());
+        for user in &lock.user_list {
+            data.extend_from_slice(user.as_ref());
+        }
+        data.extend_from_slice(&(lock.user_amount.len() as u32).to_le_bytes());
+        for amount in &lock.user_amount {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        data.extend_from_slice(&(lock.released_status.len() as u32).to_le_bytes());
+        data.extend_from_slice(&lock.released_status);
+        data.extend_from_slice(&(lock.pre_unlocked_time.len() as u32).to_le_bytes());
+        for time in &lock.pre_unlocked_time {
+            data.extend_from_slice(&time.to_le_bytes());
+        }
+        data.push(lock.claimed_token_percent);
+
+        //accounts were created with the full space, the tail stays zeroed
+        data.resize(8 + LockPda::LEN, 0);
+        data
+    }
+
+    #[test]
+    fn reads_the_baseline_layout() {
+        let lock = baseline_lock();
+        let stored = LockPda::try_deserialize(&mut &baseline_lock_data(&lock)[..]).unwrap();
+
+        assert_eq!(stored.authority, lock.authority);
+        assert_eq!(stored.end_time, lock.end_time);
+        assert_eq!(stored.first_release, 12.5);
+        assert_eq!(stored.amount_per_vesting, 17.5);
+        assert_eq!(stored.user_list, lock.user_list);
+        assert_eq!(stored.user_amount, lock.user_amount);
+        assert_eq!(stored.claimed_token_percent, 30);
+    }
+
+    #[test]
+    fn converts_the_baseline_percentages() {
+        let mut lock = baseline_lock();
+        assert_eq!(lock.tge_bps(), 1_250);
+        assert_eq!(lock.cycle_bps(), 1_750);
+        assert_eq!(lock.released_amount(500), 150);
+
+        lock.first_release = 33.333;
+        lock.amount_per_vesting = 150.0;
+        lock.claimed_token_percent = 120;
+        assert_eq!(lock.tge_bps(), 3_333);
+        assert_eq!(lock.cycle_bps(), 10_000);
+        assert_eq!(lock.released_amount(500), 500);
+    }
+}
+
+//program-wide, derived from [LOCK_COUNTER_SEED]
+#[account]
+#[derive(InitSpace)]
+pub struct LockCounter {
+    pub bump: u8,
+    pub last_lock_id: u64, //0 before the first lock
+}
+
+pub const LOCK_COUNTER_SEED: &[u8] = b"lock_counter";
+
+//finds a lock by id, derived from [LOCK_ID_SEED, lock_id as le bytes]
+#[account]
+#[derive(InitSpace)]
+pub struct LockIdLookup {
+    pub bump: u8,
+    pub lock_id: u64,
+    pub lock: Pubkey,
+}
+
+pub const LOCK_ID_SEED: &[u8] = b"lock_id";
+
+pub const LOCKS_PER_PAGE: u64 = 32;
+
+//locks created for an owner, derived from [OWNER_STATS_SEED, owner]
+#[account]
+#[derive(InitSpace)]
+pub struct OwnerLockStats {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub lock_count: u64, //the next lock goes to page lock_count / LOCKS_PER_PAGE
+}
+
+pub const OWNER_STATS_SEED: &[u8] = b"owner_stats";
+
+//locks of an spl_mint, derived from [MINT_STATS_SEED, spl_mint]
+#[account]
+#[derive(InitSpace)]
+pub struct MintLockStats {
+    pub bump: u8,
+    pub spl_mint: Pubkey,
+    pub lock_count: u64, //the next lock goes to page lock_count / LOCKS_PER_PAGE
+    pub locked_amount: u64, //tokens of the mint held by locks, lp locks hold another mint and are only listed
+}
+
+pub const MINT_STATS_SEED: &[u8] = b"mint_stats";
+
+//one page of lock addresses, derived from [OWNER_INDEX_SEED or MINT_INDEX_SEED, key, page as le bytes]
+#[account]
+#[derive(InitSpace)]
+pub struct LockIndexPage {
+    pub bump: u8,
+    pub key: Pubkey, //the owner or the spl_mint
+    pub page: u64,
+    #[max_len(LOCKS_PER_PAGE)]
+    pub locks: Vec<Pubkey>,
+}
+
+pub const OWNER_INDEX_SEED: &[u8] = b"owner_index";
+
+pub const MINT_INDEX_SEED: &[u8] = b"mint_index";
+
+pub const CONFIG_SEED: &[u8] = b"config";
+
+pub const FEE_EXEMPTION_SEED: &[u8] = b"fee_exemption";
+
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramConfig {
+    pub bump: u8,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub paused: bool,
+    pub max_vesting_beneficiaries: u32,
+    pub max_lock_duration: u64, //seconds, 0 is unlimited
+    pub treasury: Pubkey,
+    pub flat_fee_lamports: u64,
+    pub fee_bps: u16, //fee on locked tokens
+}
+
+impl ProgramConfig {
+    pub fn check_lock_duration(&self, block_time: u64, end_time: u64) -> Result<()> {
+        if self.max_lock_duration > 0 && end_time.saturating_sub(block_time) > self.max_lock_duration {
+            return Err(LockError::LockTooLong.into());
+        }
+        Ok(())
+    }
+}
+
+//mint or wallet that does not pay lock fees
+#[account]
+#[derive(InitSpace)]
+pub struct FeeExemption {
+    pub bump: u8,
+    pub target: Pubkey,
 }
@@ -0,0 +1,93 @@
+use anchor_lang::{ prelude::*, system_program };
+
+use crate::error::*;
+
+//creates a program owned pda, also when someone already sent lamports to the address
+pub fn create_pda_account<'a>(
+    payer: AccountInfo<'a>,
+    new_account: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    space: usize,
+    signer_seeds: &[&[u8]]
+) -> Result<()> {
+    let required_lamports = Rent::get()?.minimum_balance(space);
+    let signer = &[signer_seeds];
+
+    if new_account.lamports() == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program,
+                system_program::CreateAccount {
+                    from: payer,
+                    to: new_account,
+                },
+                signer
+            ),
+            required_lamports,
+            space as u64,
+            &crate::ID
+        );
+    }
+
+    let top_up_lamports = required_lamports.saturating_sub(new_account.lamports());
+    if top_up_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(system_program.clone(), system_program::Transfer {
+                from: payer,
+                to: new_account.clone(),
+            }),
+            top_up_lamports
+        )?;
+    }
+
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: new_account.clone(),
+            },
+            signer
+        ),
+        space as u64
+    )?;
+
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program,
+            system_program::Assign {
+                account_to_assign: new_account,
+            },
+            signer
+        ),
+        &crate::ID
+    )
+}
+
+//reads the program account at `seeds`, or creates it with `init(bump)` the first time
+pub fn load_or_create<'info, T: AccountSerialize + AccountDeserialize>(
+    account: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    error: LockError,
+    init: impl FnOnce(u8) -> T
+) -> Result<T> {
+    let (expected, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+    require_keys_eq!(account.key(), expected, error);
+
+    if account.owner == &crate::ID {
+        return T::try_deserialize(&mut &account.try_borrow_data()?[..]);
+    }
+
+    let bump_seed = [bump];
+    let mut signer_seeds = seeds.to_vec();
+    signer_seeds.push(&bump_seed);
+    create_pda_account(payer.clone(), account.clone(), system_program.clone(), space, &signer_seeds)?;
+
+    Ok(init(bump))
+}
+
+pub fn save<T: AccountSerialize>(account: &AccountInfo, value: &T) -> Result<()> {
+    value.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])
+}
@@ -0,0 +1,73 @@
+use anchor_lang::{ prelude::*, system_program };
+
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::error::*;
+use crate::state::*;
+use crate::utils::BPS_DENOMINATOR;
+
+//returns the flat sol fee and the token fee for locking `amount`.
+//the token fee is rounded down and paid on top of the locked amount.
+pub fn lock_fees(
+    config: &ProgramConfig,
+    fee_exemption: &Option<Account<FeeExemption>>,
+    exempt_keys: &[Pubkey],
+    amount: u64
+) -> Result<(u64, u64)> {
+    if let Some(fee_exemption) = fee_exemption {
+        if exempt_keys.contains(&fee_exemption.target) {
+            return Ok((0, 0));
+        }
+    }
+
+    let fee_amount = (amount as u128)
+        .checked_mul(config.fee_bps as u128)
+        .ok_or(LockError::MathOverflow)?
+        .checked_div(BPS_DENOMINATOR as u128)
+        .ok_or(LockError::MathOverflow)?;
+
+    Ok((config.flat_fee_lamports, fee_amount as u64))
+}
+
+//keys a fee exemption can match: the locked mint, the signer and, for lp locks, the pool mints
+//read from a verified pool. mints passed as arguments are left out, anyone could claim an exempt one.
+pub fn exempt_keys(mint: Pubkey, signer: Pubkey, pool_mints: Option<(Pubkey, Pubkey)>) -> Vec<Pubkey> {
+    let mut keys = vec![mint, signer];
+    if let Some((token_mint_a, token_mint_b)) = pool_mints {
+        keys.push(token_mint_a);
+        keys.push(token_mint_b);
+    }
+    keys
+}
+
+//the treasury token account is only passed when there is a token fee to pay
+pub fn fee_token_account<'info>(
+    treasury_spl_ata: &Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    fee_amount: u64
+) -> Result<Option<AccountInfo<'info>>> {
+    if fee_amount == 0 {
+        return Ok(None);
+    }
+    match treasury_spl_ata {
+        Some(treasury_spl_ata) => Ok(Some(treasury_spl_ata.to_account_info())),
+        None => Err(LockError::MissingTreasuryTokenAccount.into()),
+    }
+}
+
+pub fn transfer_fee_lamports<'a>(
+    payer: AccountInfo<'a>,
+    treasury: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    lamports: u64
+) -> Result<()> {
+    if lamports == 0 {
+        return Ok(());
+    }
+    system_program::transfer(
+        CpiContext::new(system_program, system_program::Transfer {
+            from: payer,
+            to: treasury,
+        }),
+        lamports
+    )
+}
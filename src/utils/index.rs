@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::utils::{ load_or_create, save };
+
+//index accounts of a new lock, the pages are picked by the current lock counts:
+//owner_index at [OWNER_INDEX_SEED, owner, owner_stats.lock_count / LOCKS_PER_PAGE]
+//and mint_index at [MINT_INDEX_SEED, spl_mint, mint_stats.lock_count / LOCKS_PER_PAGE]
+#[derive(Accounts)]
+pub struct LockIndex<'info> {
+    ///CHECK: created or checked in the handler at [OWNER_STATS_SEED, owner]
+    #[account(mut)]
+    pub owner_stats: AccountInfo<'info>,
+
+    ///CHECK: created or checked in the handler
+    #[account(mut)]
+    pub owner_index: AccountInfo<'info>,
+
+    ///CHECK: created or checked in the handler at [MINT_STATS_SEED, spl_mint]
+    #[account(mut)]
+    pub mint_stats: AccountInfo<'info>,
+
+    ///CHECK: created or checked in the handler
+    #[account(mut)]
+    pub mint_index: AccountInfo<'info>,
+}
+
+impl<'info> LockIndex<'info> {
+    //lists a new lock under its owner and spl_mint, and counts its tokens when it holds the spl_mint
+    pub fn record(
+        &self,
+        lock: Pubkey,
+        header: &LockHeader,
+        payer: AccountInfo<'info>,
+        system_program: AccountInfo<'info>
+    ) -> Result<()> {
+        let owner = header.authority;
+        let spl_mint = header.spl_mint;
+
+        list_under_owner(&self.owner_stats, &self.owner_index, owner, lock, &payer, &system_program)?;
+
+        let mut mint_stats = load_or_create(
+            &self.mint_stats,
+            &[MINT_STATS_SEED, spl_mint.as_ref()],
+            8 + MintLockStats::INIT_SPACE,
+            &payer,
+            &system_program,
+            LockError::InvalidLockIndexAccount,
+            |bump| MintLockStats { bump, spl_mint, lock_count: 0, locked_amount: 0 }
+        )?;
+        push_lock(&self.mint_index, MINT_INDEX_SEED, spl_mint, mint_stats.lock_count, lock, &payer, &system_program)?;
+        mint_stats.lock_count = mint_stats.lock_count.checked_add(1).ok_or(LockError::MathOverflow)?;
+        if header.vault_mint == spl_mint {
+            mint_stats.locked_amount = mint_stats.locked_amount
+                .checked_add(header.lock_amount)
+                .ok_or(LockError::MathOverflow)?;
+        }
+        save(&self.mint_stats, &mint_stats)
+    }
+}
+
+//index pages listing an existing lock. clients find them by reading the pages of the
+//lock's owner and spl_mint, a lock stays on the page it was first listed on.
+#[derive(Accounts)]
+pub struct LockIndexPages<'info> {
+    ///CHECK: checked in the handler, the owner_index page listing the lock
+    #[account(mut)]
+    pub owner_index: AccountInfo<'info>,
+
+    ///CHECK: checked in the handler, the mint_index page listing the lock
+    #[account(mut)]
+    pub mint_index: AccountInfo<'info>,
+}
+
+impl<'info> LockIndexPages<'info> {
+    //takes a closed lock off both indexes, the page keeps its rent and lock_count isn't lowered
+    pub fn remove(&self, lock: Pubkey, header: &LockHeader) -> Result<()> {
+        remove_lock(&self.owner_index, OWNER_INDEX_SEED, header.authority, lock)?;
+        remove_lock(&self.mint_index, MINT_INDEX_SEED, header.spl_mint, lock)
+    }
+}
+
+//owner index accounts of a lock changing hands: the page of the previous owner listing the lock,
+//and the new owner's stats and current page at [OWNER_INDEX_SEED, new owner, lock_count / LOCKS_PER_PAGE]
+#[derive(Accounts)]
+pub struct OwnerIndexMove<'info> {
+    ///CHECK: checked in the handler, the owner_index page of the previous owner listing the lock
+    #[account(mut)]
+    pub previous_owner_index: AccountInfo<'info>,
+
+    ///CHECK: created or checked in the handler at [OWNER_STATS_SEED, new owner]
+    #[account(mut)]
+    pub owner_stats: AccountInfo<'info>,
+
+    ///CHECK: created or checked in the handler
+    #[account(mut)]
+    pub owner_index: AccountInfo<'info>,
+}
+
+impl<'info> OwnerIndexMove<'info> {
+    //moves the lock from the previous owner's index to the new owner's
+    pub fn move_lock(
+        &self,
+        lock: Pubkey,
+        previous_owner: Pubkey,
+        new_owner: Pubkey,
+        payer: AccountInfo<'info>,
+        system_program: AccountInfo<'info>
+    ) -> Result<()> {
+        remove_lock(&self.previous_owner_index, OWNER_INDEX_SEED, previous_owner, lock)?;
+        list_under_owner(&self.owner_stats, &self.owner_index, new_owner, lock, &payer, &system_program)
+    }
+}
+
+//adds tokens deposited into an existing lock to the running total of its spl_mint
+pub fn add_locked_amount(mint_stats: &AccountInfo, header: &LockHeader, amount: u64) -> Result<()> {
+    update_locked_amount(mint_stats, header, |locked_amount| locked_amount.checked_add(amount).ok_or(LockError::MathOverflow.into()))
+}
+
+//removes unlocked tokens from the running total of the lock's spl_mint
+pub fn remove_locked_amount(mint_stats: &AccountInfo, header: &LockHeader, amount: u64) -> Result<()> {
+    update_locked_amount(mint_stats, header, |locked_amount| locked_amount.checked_sub(amount).ok_or(LockError::MathOverflow.into()))
+}
+
+//lp locks hold another mint and don't count, every other lock was counted when it was listed
+fn update_locked_amount(
+    mint_stats: &AccountInfo,
+    header: &LockHeader,
+    update: impl FnOnce(u64) -> Result<u64>
+) -> Result<()> {
+    let (expected, _) = Pubkey::find_program_address(&[MINT_STATS_SEED, header.spl_mint.as_ref()], &crate::ID);
+    require_keys_eq!(mint_stats.key(), expected, LockError::InvalidLockIndexAccount);
+
+    if header.vault_mint != header.spl_mint {
+        return Ok(());
+    }
+    require_keys_eq!(*mint_stats.owner, crate::ID, LockError::InvalidLockIndexAccount);
+
+    let mut stats = MintLockStats::try_deserialize(&mut &mint_stats.try_borrow_data()?[..])?;
+    stats.locked_amount = update(stats.locked_amount)?;
+    save(mint_stats, &stats)
+}
+
+//lists `lock` under `owner` and counts it in the owner's stats
+fn list_under_owner<'info>(
+    owner_stats: &AccountInfo<'info>,
+    owner_index: &AccountInfo<'info>,
+    owner: Pubkey,
+    lock: Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>
+) -> Result<()> {
+    let mut stats = load_or_create(
+        owner_stats,
+        &[OWNER_STATS_SEED, owner.as_ref()],
+        8 + OwnerLockStats::INIT_SPACE,
+        payer,
+        system_program,
+        LockError::InvalidLockIndexAccount,
+        |bump| OwnerLockStats { bump, owner, lock_count: 0 }
+    )?;
+    push_lock(owner_index, OWNER_INDEX_SEED, owner, stats.lock_count, lock, payer, system_program)?;
+    stats.lock_count = stats.lock_count.checked_add(1).ok_or(LockError::MathOverflow)?;
+    save(owner_stats, &stats)
+}
+
+//appends `lock` to page count / LOCKS_PER_PAGE of the owner or mint index, a new page is created when it fills up
+fn push_lock<'info>(
+    index: &AccountInfo<'info>,
+    seed: &[u8],
+    key: Pubkey,
+    count: u64,
+    lock: Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>
+) -> Result<()> {
+    let page = count / LOCKS_PER_PAGE;
+    let mut index_page = load_or_create(
+        index,
+        &[seed, key.as_ref(), &page.to_le_bytes()],
+        8 + LockIndexPage::INIT_SPACE,
+        payer,
+        system_program,
+        LockError::InvalidLockIndexAccount,
+        |bump| LockIndexPage { bump, key, page, locks: Vec::new() }
+    )?;
+    index_page.locks.push(lock);
+    save(index, &index_page)
+}
+
+//drops `lock` from an owner or mint index page of `key`
+fn remove_lock(index: &AccountInfo, seed: &[u8], key: Pubkey, lock: Pubkey) -> Result<()> {
+    require_keys_eq!(*index.owner, crate::ID, LockError::InvalidLockIndexAccount);
+    let mut index_page = LockIndexPage::try_deserialize(&mut &index.try_borrow_data()?[..])?;
+
+    let page = index_page.page.to_le_bytes();
+    let expected = Pubkey::create_program_address(&[seed, key.as_ref(), &page, &[index_page.bump]], &crate::ID)
+        .map_err(|_| LockError::InvalidLockIndexAccount)?;
+    require_keys_eq!(index.key(), expected, LockError::InvalidLockIndexAccount);
+
+    let position = index_page.locks
+        .iter()
+        .position(|listed| *listed == lock)
+        .ok_or(LockError::InvalidLockIndexAccount)?;
+    index_page.locks.remove(position);
+    save(index, &index_page)
+}
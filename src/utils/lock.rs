@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::error::*;
+use crate::utils::{ create_pda_account, load_or_create, save };
+
+//instructions shared by every lock kind take one optional account per kind,
+//exactly one of them must be passed
+pub fn lock_header<'a, 'info>(
+    token_lock: &'a mut Option<Box<Account<'info, TokenLock>>>,
+    lp_lock: &'a mut Option<Box<Account<'info, LpLock>>>,
+    vesting_lock: &'a mut Option<Box<Account<'info, VestingLock>>>
+) -> Result<(AccountInfo<'info>, &'a mut LockHeader)> {
+    match (token_lock, lp_lock, vesting_lock) {
+        (Some(lock), None, None) => Ok((lock.to_account_info(), &mut lock.header)),
+        (None, Some(lock), None) => Ok((lock.to_account_info(), &mut lock.header)),
+        (None, None, Some(lock)) => Ok((lock.to_account_info(), &mut lock.header)),
+        _ => Err(LockError::InvalidLockAccount.into()),
+    }
+}
+
+//closes whichever lock account was passed and sends its rent to `destination`
+pub fn close_lock_account<'info>(
+    token_lock: &Option<Box<Account<'info, TokenLock>>>,
+    lp_lock: &Option<Box<Account<'info, LpLock>>>,
+    vesting_lock: &Option<Box<Account<'info, VestingLock>>>,
+    destination: AccountInfo<'info>
+) -> Result<()> {
+    match (token_lock, lp_lock, vesting_lock) {
+        (Some(lock), None, None) => lock.close(destination),
+        (None, Some(lock), None) => lock.close(destination),
+        (None, None, Some(lock)) => lock.close(destination),
+        _ => Err(LockError::InvalidLockAccount.into()),
+    }
+}
+
+//accounts handing out the id of a new lock: lock_counter at [LOCK_COUNTER_SEED] and
+//lock_id_lookup at [LOCK_ID_SEED, last_lock_id + 1]. clients derive lock_id_lookup from
+//the counter, a concurrent lock makes the address stale.
+#[derive(Accounts)]
+pub struct LockIds<'info> {
+    ///CHECK: created or checked in the handler at [LOCK_COUNTER_SEED]
+    #[account(mut)]
+    pub lock_counter: AccountInfo<'info>,
+
+    ///CHECK: created in the handler at [LOCK_ID_SEED, lock_counter.last_lock_id + 1]
+    #[account(mut)]
+    pub lock_id_lookup: AccountInfo<'info>,
+}
+
+impl<'info> LockIds<'info> {
+    //hands out the next lock id and creates its LockIdLookup pointing at `lock`
+    pub fn assign(&self, lock: Pubkey, payer: AccountInfo<'info>, system_program: AccountInfo<'info>) -> Result<u64> {
+        let mut lock_counter = load_or_create(
+            &self.lock_counter,
+            &[LOCK_COUNTER_SEED],
+            8 + LockCounter::INIT_SPACE,
+            &payer,
+            &system_program,
+            LockError::InvalidLockIdAccount,
+            |bump| LockCounter { bump, last_lock_id: 0 }
+        )?;
+        let lock_id = lock_counter.last_lock_id.checked_add(1).ok_or(LockError::MathOverflow)?;
+        lock_counter.last_lock_id = lock_id;
+        save(&self.lock_counter, &lock_counter)?;
+
+        let lock_id_bytes = lock_id.to_le_bytes();
+        let (expected, bump) = Pubkey::find_program_address(&[LOCK_ID_SEED, &lock_id_bytes], &crate::ID);
+        require_keys_eq!(self.lock_id_lookup.key(), expected, LockError::InvalidLockIdAccount);
+
+        create_pda_account(
+            payer,
+            self.lock_id_lookup.clone(),
+            system_program,
+            8 + LockIdLookup::INIT_SPACE,
+            &[LOCK_ID_SEED, &lock_id_bytes, &[bump]]
+        )?;
+        save(&self.lock_id_lookup, &LockIdLookup { bump, lock_id, lock })?;
+
+        Ok(lock_id)
+    }
+}
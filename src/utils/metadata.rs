@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{ self, mpl_token_metadata },
+    token_2022::spl_token_2022::{
+        self,
+        extension::{ metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions },
+    },
+    token_interface::Mint,
+};
+
+use crate::error::*;
+
+//checks spl_mint_metadata_pda really is the metadata of the mint, so events can be trusted.
+//passing the system program opts out for mints without metadata.
+//a token-2022 metadata pointer wins over the metaplex pda when the mint has one.
+pub fn check_mint_metadata(mint_account: &InterfaceAccount<Mint>, metadata_pda: &AccountInfo) -> Result<()> {
+    if metadata_pda.key() == System::id() {
+        return Ok(());
+    }
+
+    let mint_info = mint_account.to_account_info();
+    let pointer_target = {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        match mint.get_extension::<MetadataPointer>() {
+            Ok(pointer) => Option::<Pubkey>::from(pointer.metadata_address),
+            Err(_) => None,
+        }
+    };
+
+    match pointer_target {
+        //metadata stored in the mint itself, or in an account the pointer names
+        Some(target) => {
+            if metadata_pda.key() != target {
+                return Err(LockError::NotValidToken.into());
+            }
+            //an external metadata account must be written by token-2022 or metaplex, not by anyone
+            let trusted_owner = metadata_pda.owner == &spl_token_2022::ID || metadata_pda.owner == &metadata::ID;
+            if target != mint_info.key() && (metadata_pda.data_is_empty() || !trusted_owner) {
+                return Err(LockError::NotValidToken.into());
+            }
+        }
+        None => {
+            let expected = mpl_token_metadata::accounts::Metadata::find_pda(&mint_info.key()).0;
+            if metadata_pda.key() != expected || metadata_pda.owner != &metadata::ID {
+                return Err(LockError::NotValidToken.into());
+            }
+        }
+    }
+    Ok(())
+}
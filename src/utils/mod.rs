@@ -1,3 +1,15 @@
 pub mod token;
+pub mod vesting;
+pub mod fee;
+pub mod account;
+pub mod lock;
+pub mod metadata;
+pub mod index;
 
 pub use token::*;
+pub use vesting::*;
+pub use fee::*;
+pub use account::*;
+pub use lock::*;
+pub use metadata::*;
+pub use index::*;
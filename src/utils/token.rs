@@ -1,86 +1,192 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     token_2022::{
-        self,
         spl_token_2022::{
             self,
+            onchain,
             extension::{
+                default_account_state::DefaultAccountState,
+                permanent_delegate::PermanentDelegate,
+                transfer_fee::{ instruction::harvest_withheld_tokens_to_mint, TransferFeeAmount, TransferFeeConfig },
                 ExtensionType,
                 StateWithExtensions,
             },
+            state::AccountState,
         },
     },
-    token_interface::{ spl_token_2022::extension::BaseStateWithExtensions, Mint },
+    token_interface::{ spl_token_2022::extension::BaseStateWithExtensions, Mint, TokenAccount },
 };
 
+use crate::error::*;
+
+//hook_accounts are the extra accounts of the mint's transfer hook program, resolved
+//from its validation account. they are ignored for mints without a transfer hook.
 pub fn transfer_from_user_to_pool_vault<'a>(
     authority: AccountInfo<'a>,
     from: AccountInfo<'a>,
     to_vault: AccountInfo<'a>,
-    mint: AccountInfo<'a>,
+    mint: &InterfaceAccount<'a, Mint>,
     token_program: AccountInfo<'a>,
     amount: u64,
-    mint_decimals: u8
+    hook_accounts: &[AccountInfo<'a>]
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
     }
-    token_2022::transfer_checked(
-        CpiContext::new(token_program.to_account_info(), token_2022::TransferChecked {
-            from,
-            to: to_vault,
-            authority,
-            mint,
-        }),
+    onchain::invoke_transfer_checked(
+        token_program.key,
+        from,
+        mint.to_account_info(),
+        to_vault,
+        authority,
+        hook_accounts,
         amount,
-        mint_decimals
-    )
+        mint.decimals,
+        &[]
+    )?;
+    Ok(())
 }
 
-pub fn transfer_from_pool_vault_to_user<'a>(
-    authority: AccountInfo<'a>,
-    from_vault: AccountInfo<'a>,
-    to: AccountInfo<'a>,
-    mint: AccountInfo<'a>,
-    token_program: AccountInfo<'a>,
+//accounts of a transfer signed by a program pda, hook_accounts as in transfer_from_user_to_pool_vault
+pub struct TransferAccounts<'a, 'b> {
+    pub authority: AccountInfo<'a>,
+    pub from: AccountInfo<'a>,
+    pub to: AccountInfo<'a>,
+    pub mint: &'b InterfaceAccount<'a, Mint>,
+    pub token_program: AccountInfo<'a>,
+    pub hook_accounts: &'b [AccountInfo<'a>],
+}
+
+pub fn transfer_from_pool_vault_to_user(
+    accounts: TransferAccounts,
     amount: u64,
-    mint_decimals: u8,
     signer_seeds: &[&[&[u8]]]
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
     }
-    token_2022::transfer_checked(
-        CpiContext::new_with_signer(
-            token_program.to_account_info(),
-            token_2022::TransferChecked {
-                from: from_vault,
-                to,
-                authority,
-                mint,
-            },
-            signer_seeds
-        ),
+    onchain::invoke_transfer_checked(
+        accounts.token_program.key,
+        accounts.from,
+        accounts.mint.to_account_info(),
+        accounts.to,
+        accounts.authority,
+        accounts.hook_accounts,
         amount,
-        mint_decimals
-    )
+        accounts.mint.decimals,
+        signer_seeds
+    )?;
+    Ok(())
+}
+
+pub struct MintPolicy {
+    //the delegate can move tokens out of the lock vault
+    pub permanent_delegate: bool,
 }
 
-pub fn is_supported_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool> {
+//checks the mint extensions against the lock policy. extensions that hand the issuer control over
+//the vault are rejected with UnsafeMintExtension, extensions that are not listed with UnsupportedMint.
+pub fn check_mint_extensions(mint_account: &InterfaceAccount<Mint>) -> Result<MintPolicy> {
     let mint_info = mint_account.to_account_info();
     let mint_data = mint_info.try_borrow_data()?;
     let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
     let extensions = mint.get_extension_types()?;
+
+    let mut policy = MintPolicy { permanent_delegate: false };
     for e in extensions {
-        if
-            e != ExtensionType::TransferFeeConfig &&
-            e != ExtensionType::MetadataPointer &&
-            e != ExtensionType::TokenMetadata
-        {
-            return Ok(false);
+        match e {
+            ExtensionType::TransferFeeConfig |
+            ExtensionType::MetadataPointer |
+            ExtensionType::TokenMetadata |
+            ExtensionType::InterestBearingConfig |
+            ExtensionType::TransferHook |
+            ExtensionType::MintCloseAuthority => {}
+            ExtensionType::DefaultAccountState => {
+                //a frozen vault only pays out once the freeze authority thaws it
+                let default_state = mint.get_extension::<DefaultAccountState>()?;
+                if default_state.state == AccountState::Frozen as u8 {
+                    return Err(LockError::UnsafeMintExtension.into());
+                }
+            }
+            //tokens could never leave the vault
+            ExtensionType::NonTransferable => {
+                return Err(LockError::UnsafeMintExtension.into());
+            }
+            ExtensionType::PermanentDelegate => {
+                let delegate = mint.get_extension::<PermanentDelegate>()?;
+                policy.permanent_delegate = Option::<Pubkey>::from(delegate.delegate).is_some();
+            }
+            _ => {
+                return Err(LockError::UnsupportedMint.into());
+            }
         }
     }
-    Ok(true)
+    Ok(policy)
+}
+
+//whether the mint has a permanent delegate, without applying the extension policy
+pub fn has_permanent_delegate(mint_account: &InterfaceAccount<Mint>) -> Result<bool> {
+    let mint_info = mint_account.to_account_info();
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let permanent_delegate = match mint.get_extension::<PermanentDelegate>() {
+        Ok(delegate) => Option::<Pubkey>::from(delegate.delegate).is_some(),
+        Err(_) => false,
+    };
+    Ok(permanent_delegate)
 }
 
+//what `to` received since `balance_before`, transfer fees are withheld from the destination
+pub fn received_amount(to: &mut InterfaceAccount<TokenAccount>, balance_before: u64) -> Result<u64> {
+    to.reload()?;
+    to.amount.checked_sub(balance_before).ok_or(LockError::MathOverflow.into())
+}
+
+//fee withheld when `amount` is sent, 0 for mints without a TransferFeeConfig
+pub fn transfer_fee_amount(mint_account: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint_account.to_account_info();
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) =>
+            config
+                .calculate_epoch_fee(Clock::get()?.epoch, amount)
+                .ok_or(LockError::MathOverflow)?,
+        Err(_) => 0,
+    };
+    Ok(fee)
+}
+
+//amount to send so that `amount` arrives after the transfer fee
+pub fn amount_with_transfer_fee(mint_account: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint_account.to_account_info();
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) =>
+            config
+                .calculate_inverse_epoch_fee(Clock::get()?.epoch, amount)
+                .ok_or(LockError::MathOverflow)?,
+        Err(_) => 0,
+    };
+    amount.checked_add(fee).ok_or(LockError::MathOverflow.into())
+}
 
+//transfer fees withheld in a token-2022 account, the account can't be closed until they are harvested
+pub fn withheld_transfer_fees(token_account: &InterfaceAccount<TokenAccount>) -> Result<u64> {
+    let account_info = token_account.to_account_info();
+    let account_data = account_info.try_borrow_data()?;
+    let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account_data)?;
+    let withheld = match account.get_extension::<TransferFeeAmount>() {
+        Ok(fee_amount) => u64::from(fee_amount.withheld_amount),
+        Err(_) => 0,
+    };
+    Ok(withheld)
+}
+
+//moves the fees withheld in `source` to the mint, harvesting needs no signer
+pub fn harvest_withheld_fees<'a>(token_program: AccountInfo<'a>, mint: AccountInfo<'a>, source: AccountInfo<'a>) -> Result<()> {
+    let ix = harvest_withheld_tokens_to_mint(token_program.key, mint.key, &[source.key])?;
+    anchor_lang::solana_program::program::invoke(&ix, &[mint, source, token_program])?;
+    Ok(())
+}
@@ -0,0 +1,466 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+use crate::state::*;
+use crate::error::*;
+use crate::utils::create_pda_account;
+
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+//amount vested after the tge release plus `cycles` completed cycles.
+//partial amounts are rounded down, once the schedule reaches 100% the
+//full amount is returned so the last claim sweeps any rounding dust.
+pub fn vested_amount(total_amount: u64, tge_bps: u16, cycle_bps: u16, cycles: u64) -> Result<u64> {
+    let vested_bps = (tge_bps as u128)
+        .checked_add((cycle_bps as u128).checked_mul(cycles as u128).ok_or(LockError::MathOverflow)?)
+        .ok_or(LockError::MathOverflow)?;
+
+    if vested_bps >= (BPS_DENOMINATOR as u128) {
+        return Ok(total_amount);
+    }
+
+    let amount = (total_amount as u128)
+        .checked_mul(vested_bps)
+        .ok_or(LockError::MathOverflow)?
+        .checked_div(BPS_DENOMINATOR as u128)
+        .ok_or(LockError::MathOverflow)?;
+
+    Ok(amount as u64)
+}
+
+//amount vested `elapsed` seconds into a linear release of `duration` seconds.
+//the tge release is unlocked at the start, the rest vests per second and is rounded down.
+pub fn linear_vested_amount(total_amount: u64, tge_bps: u16, elapsed: u64, duration: u64) -> Result<u64> {
+    if elapsed >= duration {
+        return Ok(total_amount);
+    }
+
+    let tge_amount = vested_amount(total_amount, tge_bps, 0, 0)?;
+    let linear_amount = ((total_amount - tge_amount) as u128)
+        .checked_mul(elapsed as u128)
+        .ok_or(LockError::MathOverflow)?
+        .checked_div(duration as u128)
+        .ok_or(LockError::MathOverflow)?;
+
+    Ok(tge_amount + (linear_amount as u64))
+}
+
+//marks everything vested for `beneficiary` at block_time as released and
+//moves it out of the lock amount, returns the amount to transfer.
+pub fn release_vested(
+    vesting_lock: &mut VestingLock,
+    beneficiary: &mut VestingBeneficiary,
+    block_time: u64
+) -> Result<u64> {
+    //check unlock_time for first release.
+    if block_time < vesting_lock.header.end_time {
+        return Err(LockError::NotUnlockTime.into());
+    }
+
+    let user_amount = beneficiary.amount;
+    let released_amount = beneficiary.released_amount;
+
+    let rest_time = block_time - vesting_lock.header.end_time;
+
+    let (vested, is_first_period) = match vesting_lock.vesting_type {
+        VestingType::Tranche => {
+            //the tge release unlocks at end_time, then one cycle unlocks per vesting_period after the first one
+            //a zero period can only come from a lock created before the schedule checks
+            let period_secs = vesting_lock.vesting_period
+                .checked_mul(24 * 3600)
+                .filter(|period_secs| *period_secs > 0)
+                .ok_or(LockError::InvalidVestingPeriod)?;
+            let cycles = (rest_time / period_secs).saturating_sub(1);
+            (vested_amount(user_amount, vesting_lock.tge_bps, vesting_lock.cycle_bps, cycles)?, cycles == 0)
+        }
+        VestingType::Linear => {
+            let duration = vesting_lock.vesting_end_time.saturating_sub(vesting_lock.header.end_time);
+            (linear_vested_amount(user_amount, vesting_lock.tge_bps, rest_time, duration)?, false)
+        }
+    };
+
+    if vested <= released_amount {
+        if is_first_period && released_amount > 0 {
+            return Err(LockError::AlreadyDidFirstClaim.into());
+        }
+        return Err(LockError::NotPerVestingUnlockTime.into());
+    }
+
+    let unlock_amount = vested - released_amount;
+
+    beneficiary.released_amount = vested;
+    beneficiary.last_claim_time = block_time;
+
+    let header = &mut vesting_lock.header;
+    header.lock_amount = header.lock_amount
+        .checked_sub(unlock_amount)
+        .ok_or(LockError::LowBalance)?;
+    header.unlocked_amount = header.unlocked_amount
+        .checked_add(unlock_amount)
+        .ok_or(LockError::MathOverflow)?;
+
+    Ok(unlock_amount)
+}
+
+impl VestingSchedule {
+    //schedule checks shared by lock_vesting and create_vesting_pool
+    pub fn validate(&self, lock_time: u64) -> Result<()> {
+        if self.tge_bps > BPS_DENOMINATOR {
+            return Err(LockError::InvalidTgeBps.into());
+        }
+
+        match self.vesting_type {
+            VestingType::Tranche => {
+                //a zero cycle never reaches 100% and would leave the rest stuck
+                if self.cycle_bps == 0 || self.cycle_bps > BPS_DENOMINATOR {
+                    return Err(LockError::InvalidCycleBps.into());
+                }
+                if self.vesting_period == 0 || self.vesting_period.checked_mul(24 * 3600).is_none() {
+                    return Err(LockError::InvalidVestingPeriod.into());
+                }
+            }
+            VestingType::Linear => {
+                if self.vesting_end_time <= lock_time {
+                    return Err(LockError::InvalidVestingSchedule.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl VestingLock {
+    //stores a validated schedule, tranche schedules have no end time
+    pub fn set_schedule(&mut self, schedule: &VestingSchedule) {
+        self.tge_bps = schedule.tge_bps;
+        self.vesting_period = schedule.vesting_period;
+        self.cycle_bps = schedule.cycle_bps;
+        self.vesting_type = schedule.vesting_type;
+        self.vesting_end_time = match schedule.vesting_type {
+            VestingType::Tranche => 0,
+            VestingType::Linear => schedule.vesting_end_time,
+        };
+    }
+}
+
+//creates one VestingBeneficiary pda per user from `beneficiary_accounts`, in the same order as user_list.
+//returns the sum of the new amounts.
+pub fn create_beneficiaries<'info>(
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    vesting_lock: Pubkey,
+    beneficiary_accounts: &[AccountInfo<'info>],
+    user_list: &[Pubkey],
+    user_amount: &[u64]
+) -> Result<u64> {
+    if user_list.len() != user_amount.len() {
+        return Err(LockError::BeneficiaryLengthMismatch.into());
+    }
+
+    if beneficiary_accounts.len() != user_list.len() {
+        return Err(LockError::InvalidBeneficiaryAccount.into());
+    }
+
+    let mut lock_amount=0;
+
+    for i in 0..user_list.len() {
+        let beneficiary_info = &beneficiary_accounts[i];
+        let (expected, bump) = Pubkey::find_program_address(
+            &[VESTING_BENEFICIARY_SEED, vesting_lock.as_ref(), user_list[i].as_ref()],
+            &crate::ID
+        );
+        require_keys_eq!(beneficiary_info.key(), expected, LockError::InvalidBeneficiaryAccount);
+
+        //the pda is already ours when the user is repeated in this batch or was added before
+        if beneficiary_info.owner == &crate::ID {
+            return Err(LockError::DuplicateBeneficiary.into());
+        }
+
+        if user_amount[i] == 0 {
+            return Err(LockError::BeneficiaryAmountZero.into());
+        }
+
+        create_pda_account(
+            payer.clone(),
+            beneficiary_info.clone(),
+            system_program.clone(),
+            8 + VestingBeneficiary::INIT_SPACE,
+            &[VESTING_BENEFICIARY_SEED, vesting_lock.as_ref(), user_list[i].as_ref(), &[bump]]
+        )?;
+
+        let beneficiary = VestingBeneficiary {
+            bump,
+            vesting_lock,
+            beneficiary: user_list[i],
+            amount: user_amount[i],
+            released_amount: 0,
+            last_claim_time: 0,
+        };
+        beneficiary.try_serialize(&mut &mut beneficiary_info.try_borrow_mut_data()?[..])?;
+
+        lock_amount = user_amount[i]
+            .checked_add(lock_amount)
+            .ok_or(LockError::MathOverflow)?;
+    }
+
+    Ok(lock_amount)
+}
+
+//closes the VestingBeneficiary pdas of an emptied vesting lock passed in `beneficiary_accounts`.
+//list beneficiaries were paid for by the lock payer and refund `payer`, merkle beneficiaries were
+//paid for by their claimant and come in pairs of the pda and the claimant wallet.
+pub fn close_beneficiaries<'info>(
+    vesting_lock: &mut VestingLock,
+    lock_pda: Pubkey,
+    beneficiary_accounts: &'info [AccountInfo<'info>],
+    payer: &AccountInfo<'info>
+) -> Result<()> {
+    let merkle = vesting_lock.merkle_root != [0u8; 32];
+    let step = if merkle { 2 } else { 1 };
+    let chunks = beneficiary_accounts.chunks_exact(step);
+    if !chunks.remainder().is_empty() {
+        return Err(LockError::InvalidBeneficiaryAccount.into());
+    }
+
+    for accounts in chunks {
+        let beneficiary = Account::<VestingBeneficiary>::try_from(&accounts[0])?;
+        require_keys_eq!(beneficiary.vesting_lock, lock_pda, LockError::InvalidBeneficiaryAccount);
+
+        let destination = if merkle {
+            require_keys_eq!(accounts[1].key(), beneficiary.beneficiary, LockError::InvalidBeneficiaryAccount);
+            &accounts[1]
+        } else {
+            payer
+        };
+        beneficiary.close(destination.clone())?;
+
+        vesting_lock.beneficiary_count = vesting_lock.beneficiary_count
+            .checked_sub(1)
+            .ok_or(LockError::MathOverflow)?;
+    }
+
+    Ok(())
+}
+
+//leaf of a merkle vesting pool, keccak256(beneficiary || amount as little endian u64)
+pub fn merkle_leaf(beneficiary: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[beneficiary.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+//each pair is hashed in sorted order, so proofs carry no left/right flags
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    for node in proof {
+        computed = if computed <= *node {
+            keccak::hashv(&[&computed, node]).to_bytes()
+        } else {
+            keccak::hashv(&[node, &computed]).to_bytes()
+        };
+    }
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tranche(tge_bps: u16, cycle_bps: u16, vesting_period: u64) -> VestingSchedule {
+        VestingSchedule { tge_bps, vesting_period, cycle_bps, vesting_type: VestingType::Tranche, vesting_end_time: 0 }
+    }
+
+    fn linear(tge_bps: u16, vesting_end_time: u64) -> VestingSchedule {
+        VestingSchedule { tge_bps, vesting_period: 0, cycle_bps: 0, vesting_type: VestingType::Linear, vesting_end_time }
+    }
+
+    fn empty_vesting_lock() -> VestingLock {
+        VestingLock {
+            header: LockHeader {
+                bump: 0,
+                seed: String::new(),
+                lock_id: 0,
+                creator: Pubkey::default(),
+                authority: Pubkey::default(),
+                pending_authority: Pubkey::default(),
+                payer: Pubkey::default(),
+                spl_mint: Pubkey::default(),
+                vault_mint: Pubkey::default(),
+                seed_mint: Pubkey::default(),
+                spl_mint_metadata_pda: Pubkey::default(),
+                permanent_delegate: false,
+                lock_amount: 0,
+                unlocked_amount: 0,
+                start_time: 0,
+                end_time: 0,
+                lock_name: String::new(),
+                extra_data: String::new(),
+            },
+            vesting_type: VestingType::Linear,
+            tge_bps: 0,
+            cycle_bps: 0,
+            vesting_period: 0,
+            vesting_end_time: 0,
+            beneficiary_count: 0,
+            finalized: false,
+            merkle_root: [0u8; 32],
+        }
+    }
+
+    fn vesting_lock(vesting_type: VestingType, tge_bps: u16, cycle_bps: u16, vesting_end_time: u64) -> VestingLock {
+        let mut vesting_lock = empty_vesting_lock();
+        vesting_lock.header.end_time = 1_000;
+        vesting_lock.vesting_type = vesting_type;
+        vesting_lock.tge_bps = tge_bps;
+        vesting_lock.cycle_bps = cycle_bps;
+        vesting_lock.vesting_period = 1;
+        vesting_lock.vesting_end_time = vesting_end_time;
+        vesting_lock
+    }
+
+    fn beneficiary(amount: u64) -> VestingBeneficiary {
+        VestingBeneficiary {
+            bump: 0,
+            vesting_lock: Pubkey::default(),
+            beneficiary: Pubkey::default(),
+            amount,
+            released_amount: 0,
+            last_claim_time: 0,
+        }
+    }
+
+    const DAY: u64 = 24 * 3600;
+
+    #[test]
+    fn rounds_bps_amounts_down() {
+        //333 * 1% = 3.33
+        assert_eq!(vested_amount(333, 100, 0, 0).unwrap(), 3);
+        //999 * 33.33% = 332.9667
+        assert_eq!(vested_amount(999, 3_333, 0, 0).unwrap(), 332);
+        //1 bps of less than 10_000 tokens vests nothing yet
+        assert_eq!(vested_amount(9_999, 1, 0, 0).unwrap(), 0);
+        assert_eq!(vested_amount(10_000, 1, 0, 0).unwrap(), 1);
+        assert_eq!(vested_amount(1_000, 1_000, 2_000, 2).unwrap(), 500);
+    }
+
+    #[test]
+    fn sweeps_rounding_dust_on_the_last_cycle() {
+        //3 cycles of 33.33% after no tge leave 0.01% undistributed until the schedule passes 100%
+        let total = 1_000_003;
+        assert_eq!(vested_amount(total, 0, 3_333, 3).unwrap(), 999_902);
+        assert_eq!(vested_amount(total, 0, 3_333, 4).unwrap(), total);
+        //reaching exactly 100% pays the full amount
+        assert_eq!(vested_amount(total, 1_000, 3_000, 3).unwrap(), total);
+
+        let mut vesting_lock = vesting_lock(VestingType::Tranche, 0, 3_333, 0);
+        vesting_lock.header.lock_amount = total;
+        let mut user = beneficiary(total);
+        let mut released = 0;
+        for day in 1..=5 {
+            released += release_vested(&mut vesting_lock, &mut user, 1_000 + day * DAY).unwrap_or(0);
+        }
+        assert_eq!(released, total);
+        assert_eq!(user.released_amount, total);
+        assert_eq!(vesting_lock.header.lock_amount, 0);
+        assert_eq!(vesting_lock.header.unlocked_amount, total);
+    }
+
+    #[test]
+    fn handles_max_amounts_without_overflow() {
+        assert_eq!(vested_amount(u64::MAX, 9_999, 0, 0).unwrap(), ((u64::MAX as u128) * 9_999 / 10_000) as u64);
+        assert_eq!(vested_amount(u64::MAX, 10_000, 0, 0).unwrap(), u64::MAX);
+        assert_eq!(vested_amount(u64::MAX, u16::MAX, u16::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(vested_amount(u64::MAX, 0, 1, u64::MAX).unwrap(), u64::MAX);
+
+        let half = linear_vested_amount(u64::MAX, 0, u64::MAX / 2, u64::MAX).unwrap();
+        assert_eq!(half, u64::MAX / 2);
+        assert_eq!(linear_vested_amount(u64::MAX, 5_000, u64::MAX - 1, u64::MAX).unwrap(), u64::MAX - 1);
+        assert_eq!(linear_vested_amount(u64::MAX, 0, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn caps_tge_and_cycle_bps_at_the_full_amount() {
+        //a full tge release vests everything at end_time
+        assert_eq!(vested_amount(1_000, 10_000, 0, 0).unwrap(), 1_000);
+        //tge and one cycle adding up to exactly 100%
+        assert_eq!(vested_amount(1_000, 4_000, 6_000, 0).unwrap(), 400);
+        assert_eq!(vested_amount(1_000, 4_000, 6_000, 1).unwrap(), 1_000);
+        //going past 100% still pays the total, never more
+        assert_eq!(vested_amount(1_000, 9_000, 6_000, 1).unwrap(), 1_000);
+        assert_eq!(vested_amount(1_000, 0, 10_000, 1).unwrap(), 1_000);
+        //no tge vests nothing before the first cycle
+        assert_eq!(vested_amount(1_000, 0, 5_000, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn releases_linear_schedules_per_second() {
+        let mut vesting_lock = vesting_lock(VestingType::Linear, 1_000, 0, 1_000 + 1_000);
+        vesting_lock.header.lock_amount = 1_000;
+        let mut user = beneficiary(1_000);
+
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 999), Err(LockError::NotUnlockTime.into()));
+        //the tge release is available at end_time
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 1_000).unwrap(), 100);
+        //900 over 1_000 seconds, 333 seconds in
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 1_333).unwrap(), 299);
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 1_333), Err(LockError::NotPerVestingUnlockTime.into()));
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 5_000).unwrap(), 601);
+        assert_eq!(user.released_amount, 1_000);
+        assert_eq!(vesting_lock.header.lock_amount, 0);
+    }
+
+    #[test]
+    fn releases_tranches_once_per_period() {
+        let mut vesting_lock = vesting_lock(VestingType::Tranche, 2_000, 4_000, 0);
+        vesting_lock.header.lock_amount = 1_000;
+        let mut user = beneficiary(1_000);
+
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 1_000).unwrap(), 200);
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 1_000 + DAY), Err(LockError::AlreadyDidFirstClaim.into()));
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 1_000 + 2 * DAY).unwrap(), 400);
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 1_000 + 2 * DAY + 1), Err(LockError::NotPerVestingUnlockTime.into()));
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 1_000 + 10 * DAY).unwrap(), 400);
+    }
+
+    #[test]
+    fn rejects_a_zero_vesting_period() {
+        let mut vesting_lock = vesting_lock(VestingType::Tranche, 2_000, 4_000, 0);
+        vesting_lock.vesting_period = 0;
+        vesting_lock.header.lock_amount = 1_000;
+        let mut user = beneficiary(1_000);
+
+        assert_eq!(release_vested(&mut vesting_lock, &mut user, 1_000 + DAY), Err(LockError::InvalidVestingPeriod.into()));
+    }
+
+    #[test]
+    fn validates_tranche_schedules() {
+        assert!(tranche(1_000, 1_000, 30).validate(100).is_ok());
+        assert!(tranche(10_000, 10_000, 1).validate(100).is_ok());
+        assert_eq!(tranche(10_001, 1_000, 30).validate(100), Err(LockError::InvalidTgeBps.into()));
+        assert_eq!(tranche(1_000, 0, 30).validate(100), Err(LockError::InvalidCycleBps.into()));
+        assert_eq!(tranche(1_000, 10_001, 30).validate(100), Err(LockError::InvalidCycleBps.into()));
+        assert_eq!(tranche(1_000, 1_000, 0).validate(100), Err(LockError::InvalidVestingPeriod.into()));
+        assert_eq!(tranche(1_000, 1_000, u64::MAX).validate(100), Err(LockError::InvalidVestingPeriod.into()));
+    }
+
+    #[test]
+    fn validates_linear_schedules() {
+        assert!(linear(0, 101).validate(100).is_ok());
+        assert_eq!(linear(0, 100).validate(100), Err(LockError::InvalidVestingSchedule.into()));
+        assert_eq!(linear(10_001, 101).validate(100), Err(LockError::InvalidTgeBps.into()));
+    }
+
+    #[test]
+    fn stores_no_end_time_for_tranche_schedules() {
+        let mut vesting_lock = empty_vesting_lock();
+
+        let mut schedule = tranche(1_000, 2_000, 30);
+        schedule.vesting_end_time = 500;
+        vesting_lock.set_schedule(&schedule);
+        assert!(vesting_lock.vesting_type == VestingType::Tranche);
+        assert_eq!((vesting_lock.tge_bps, vesting_lock.cycle_bps, vesting_lock.vesting_period), (1_000, 2_000, 30));
+        assert_eq!(vesting_lock.vesting_end_time, 0);
+
+        vesting_lock.set_schedule(&linear(1_000, 500));
+        assert_eq!(vesting_lock.vesting_end_time, 500);
+    }
+}
@@ -0,0 +1,70 @@
+//! Stand-in clmm program for the position lock tests.
+//!
+//! Loaded at the Orca Whirlpool, Raydium CLMM and Raydium CPMM addresses, it lets
+//! the tests create position and pool accounts with arbitrary data. It accepts the
+//! Orca `collect_fees` and `collect_reward` and the Raydium `decrease_liquidity_v2`
+//! as long as the position authority signed.
+
+use solana_program::{
+    account_info::{ next_account_info, AccountInfo },
+    entrypoint::ProgramResult,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+const COLLECT_FEES_DISCRIMINATOR: [u8; 8] = [164, 152, 207, 99, 30, 186, 19, 182];
+const COLLECT_REWARD_DISCRIMINATOR: [u8; 8] = [70, 5, 132, 87, 86, 235, 177, 34];
+const DECREASE_LIQUIDITY_V2_DISCRIMINATOR: [u8; 8] = [58, 127, 188, 62, 79, 82, 196, 96];
+
+//[0, data..] creates the signing account with `data` as its contents
+const WRITE_ACCOUNT_TAG: u8 = 0;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8]
+) -> ProgramResult {
+    //orca passes the position authority second, raydium passes the nft owner first
+    let authority_index = match input.get(..8) {
+        Some(discriminator) if discriminator == COLLECT_FEES_DISCRIMINATOR || discriminator == COLLECT_REWARD_DISCRIMINATOR => Some(1),
+        Some(discriminator) if discriminator == DECREASE_LIQUIDITY_V2_DISCRIMINATOR => Some(0),
+        _ => None,
+    };
+    if let Some(authority_index) = authority_index {
+        let position_authority = accounts.get(authority_index).ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if !position_authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        return Ok(());
+    }
+
+    match input.split_first() {
+        Some((&WRITE_ACCOUNT_TAG, data)) => {
+            let account_info_iter = &mut accounts.iter();
+            let payer = next_account_info(account_info_iter)?;
+            let account = next_account_info(account_info_iter)?;
+            let system_program = next_account_info(account_info_iter)?;
+
+            invoke(
+                &system_instruction::create_account(
+                    payer.key,
+                    account.key,
+                    Rent::get()?.minimum_balance(data.len()),
+                    data.len() as u64,
+                    program_id
+                ),
+                &[payer.clone(), account.clone(), system_program.clone()]
+            )?;
+            account.try_borrow_mut_data()?.copy_from_slice(data);
+            Ok(())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
@@ -0,0 +1,75 @@
+//! Minimal transfer hook for the lock tests.
+//!
+//! It registers no extra accounts and only checks that token-2022 passed its
+//! validation account, so a transfer succeeds only when the caller forwarded
+//! the hook accounts.
+
+use solana_program::{
+    account_info::{ next_account_info, AccountInfo },
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::{
+    collect_extra_account_metas_signer_seeds,
+    get_extra_account_metas_address_and_bump_seed,
+    instruction::{ ExecuteInstruction, TransferHookInstruction },
+};
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8]
+) -> ProgramResult {
+    match TransferHookInstruction::unpack(input)? {
+        TransferHookInstruction::Execute { .. } => {
+            let mint = accounts.get(1).ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let validation = accounts.get(4).ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let (expected, _) = get_extra_account_metas_address_and_bump_seed(mint.key, program_id);
+            if *validation.key != expected {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            Ok(())
+        }
+        TransferHookInstruction::InitializeExtraAccountMetaList { .. } => {
+            let account_info_iter = &mut accounts.iter();
+            let validation = next_account_info(account_info_iter)?;
+            let mint = next_account_info(account_info_iter)?;
+            let payer = next_account_info(account_info_iter)?;
+            let system_program = next_account_info(account_info_iter)?;
+
+            let (expected, bump) = get_extra_account_metas_address_and_bump_seed(mint.key, program_id);
+            if *validation.key != expected {
+                return Err(ProgramError::InvalidSeeds);
+            }
+
+            let bump_seed = [bump];
+            let signer_seeds = collect_extra_account_metas_signer_seeds(mint.key, &bump_seed);
+            let size = ExtraAccountMetaList::size_of(0)?;
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    validation.key,
+                    Rent::get()?.minimum_balance(size),
+                    size as u64,
+                    program_id
+                ),
+                &[payer.clone(), validation.clone(), system_program.clone()],
+                &[&signer_seeds]
+            )?;
+
+            let mut data = validation.try_borrow_mut_data()?;
+            ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &[])?;
+            Ok(())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
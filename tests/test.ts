@@ -1,6 +1,27 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { web3, BN } from "@coral-xyz/anchor";
+import {
+    AccountState,
+    ExtensionType,
+    MINT_SIZE,
+    TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID,
+    createAssociatedTokenAccountInstruction,
+    createInitializeDefaultAccountStateInstruction,
+    createInitializeMetadataPointerInstruction,
+    createInitializeMintInstruction,
+    createInitializeNonTransferableMintInstruction,
+    createInitializePermanentDelegateInstruction,
+    createInitializeTransferFeeConfigInstruction,
+    createInitializeTransferHookInstruction,
+    createMintToInstruction,
+    createThawAccountInstruction,
+    getAssociatedTokenAddressSync,
+    getMintLen,
+} from "@solana/spl-token";
+import { createHash } from "crypto";
+import { keccak_256 } from "@noble/hashes/sha3";
 
 describe("Solana Token Locking dApp Tests", () => {
     // Anchor provider and program
@@ -26,9 +47,44 @@ describe("Solana Token Locking dApp Tests", () => {
     const metadataProgram = new web3.PublicKey("metaqbxxUerddd12kygU6CD9WFawWmdAKRW8QyWJYzVw");
 
     // Vesting parameters
-    const firstRelease = 20.0; // 20% first release
+    const tgeBps = 2000; // 20% first release
     const vestingPeriod = 3600; // 1 hour vesting period
-    const amountPerVesting = 10.0; // 10% per vesting period
+    const cycleBps = 1000; // 10% per vesting period
+
+    // Program config PDA
+    const [configPda] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("config")],
+        program.programId
+    );
+    // Lock counter PDA, every new lock takes the next id
+    const [lockCounterPda] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("lock_counter")],
+        program.programId
+    );
+    const maxVestingBeneficiaries = 100;
+    const maxLockDuration = new BN(0); // unlimited
+
+    before(async () => {
+        const [programData] = web3.PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            new web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+        );
+
+        const existing = await provider.connection.getAccountInfo(configPda);
+        if (!existing) {
+            const txHash = await program.methods
+                .initializeConfig(maxVestingBeneficiaries, maxLockDuration)
+                .accounts({
+                    config: configPda,
+                    authority: provider.wallet.publicKey,
+                    program: program.programId,
+                    programData,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .rpc();
+            await provider.connection.confirmTransaction(txHash);
+        }
+    });
 
     beforeEach(async () => {
         // Generate new keypairs for each test
@@ -49,6 +105,74 @@ describe("Solana Token Locking dApp Tests", () => {
         return metadataPda;
     };
 
+    // Helper function to derive the lookup PDA of a lock id
+    const deriveLockIdPDA = (lockId: BN) => {
+        const [lockIdPda] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("lock_id"), lockId.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        return lockIdPda;
+    };
+
+    // Helper function to derive the accounts that hand out the next lock id
+    const nextLockIds = async () => {
+        const counter = await program.account.lockCounter.fetchNullable(lockCounterPda);
+        return {
+            lockCounter: lockCounterPda,
+            lockIdLookup: deriveLockIdPDA(counter ? counter.lastLockId.addn(1) : new BN(1)),
+        };
+    };
+
+    // Helper function to derive the running totals of a mint
+    const deriveMintStatsPDA = (mint: web3.PublicKey) => {
+        const [mintStatsPda] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("mint_stats"), mint.toBuffer()],
+            program.programId
+        );
+        return mintStatsPda;
+    };
+
+    // Helper function to derive the lock counts of an owner
+    const deriveOwnerStatsPDA = (owner: web3.PublicKey) => {
+        const [ownerStatsPda] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("owner_stats"), owner.toBuffer()],
+            program.programId
+        );
+        return ownerStatsPda;
+    };
+
+    // Helper function to derive the index accounts a new lock is listed in
+    const deriveLockIndex = async (owner: web3.PublicKey, mint: web3.PublicKey) => {
+        const ownerStats = deriveOwnerStatsPDA(owner);
+        const mintStats = deriveMintStatsPDA(mint);
+        const ownerCount = (await program.account.ownerLockStats.fetchNullable(ownerStats))?.lockCount ?? new BN(0);
+        const mintCount = (await program.account.mintLockStats.fetchNullable(mintStats))?.lockCount ?? new BN(0);
+        const page = (count: BN) => count.divn(32).toArrayLike(Buffer, "le", 8);
+
+        const [ownerIndex] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("owner_index"), owner.toBuffer(), page(ownerCount)],
+            program.programId
+        );
+        const [mintIndex] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("mint_index"), mint.toBuffer(), page(mintCount)],
+            program.programId
+        );
+        return { ownerStats, ownerIndex, mintStats, mintIndex };
+    };
+
+    // Helper function to derive a vesting beneficiary PDA
+    const deriveBeneficiaryPDA = (vestingLock: web3.PublicKey, user: web3.PublicKey) => {
+        const [beneficiaryPda] = web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("vesting_beneficiary"),
+                vestingLock.toBuffer(),
+                user.toBuffer()
+            ],
+            program.programId
+        );
+        return beneficiaryPda;
+    };
+
     it("should create a basic token lock", async () => {
         // Derive metadata PDA
         const metadataPda = await deriveMetadataPDA(projectTokenMint);
@@ -83,12 +207,20 @@ describe("Solana Token Locking dApp Tests", () => {
                 )
                 .accounts({
                     lockPda: lockPdaKp.publicKey,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(provider.wallet.publicKey, projectTokenMint),
                     splMint: projectTokenMint,
                     splMintMetadataPda: metadataPda,
                     authority: provider.wallet.publicKey,
                     owner: provider.wallet.publicKey,
                     lockPdaSplAta: lockPdaSplAta,
                     authoritySplAta: authoritySplAta,
+                    pool: null,
+                    position: null,
+                    treasury: provider.wallet.publicKey, // defaults to the admin
+                    treasurySplAta: authoritySplAta,
+                    feeExemption: null,
                     tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
                     systemProgram: web3.SystemProgram.programId,
                     associatedTokenProgram: anchor.utils.token.ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -103,12 +235,12 @@ describe("Solana Token Locking dApp Tests", () => {
             await provider.connection.confirmTransaction(txHash);
 
             // Fetch the created lock PDA
-            const lockPdaAccount = await program.account.lockPda.fetch(lockPdaKp.publicKey);
+            const lockPdaAccount = await program.account.tokenLock.fetch(lockPdaKp.publicKey);
 
             console.log("Lock PDA data:", lockPdaAccount);
 
             // Check if the lock amount is correct
-            if (!lockPdaAccount.lockAmount.eq(lockAmount)) {
+            if (!lockPdaAccount.header.lockAmount.eq(lockAmount)) {
                 throw new Error("Lock amount does not match");
             }
         } catch (error) {
@@ -141,6 +273,13 @@ describe("Solana Token Locking dApp Tests", () => {
         const userList = [provider.wallet.publicKey]; // List of users
         const userAmount = [lockAmount.toNumber()]; // Amount for each user
 
+        // One beneficiary account per user, in the same order as userList
+        const beneficiaryAccounts = userList.map((user) => ({
+            pubkey: deriveBeneficiaryPDA(vestingLockPdaKp.publicKey, user),
+            isWritable: true,
+            isSigner: false,
+        }));
+
         try {
             const txHash = await program.methods
                 .lockVesting(
@@ -148,24 +287,35 @@ describe("Solana Token Locking dApp Tests", () => {
                     vestingLockTime,
                     lockName,
                     extraData,
-                    firstRelease,
-                    vestingPeriod,
-                    amountPerVesting,
+                    {
+                        tgeBps,
+                        vestingPeriod,
+                        cycleBps,
+                        vestingType: { tranche: {} },
+                        vestingEndTime: new BN(0),
+                    },
                     userList,
                     userAmount
                 )
                 .accounts({
                     lockPda: vestingLockPdaKp.publicKey,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(provider.wallet.publicKey, projectTokenMint),
                     splMint: projectTokenMint,
                     splMintMetadataPda: metadataPda,
                     authority: provider.wallet.publicKey,
                     lockPdaSplAta: vestingLockPdaSplAta,
                     authoritySplAta: authoritySplAta,
+                    treasury: provider.wallet.publicKey, // defaults to the admin
+                    treasurySplAta: authoritySplAta,
+                    feeExemption: null,
                     systemProgram: web3.SystemProgram.programId,
                     associatedTokenProgram: anchor.utils.token.ASSOCIATED_TOKEN_PROGRAM_ID,
                     tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
                     rent: web3.SYSVAR_RENT_PUBKEY,
                 })
+                .remainingAccounts(beneficiaryAccounts)
                 .signers([vestingLockPdaKp])
                 .rpc();
 
@@ -175,12 +325,12 @@ describe("Solana Token Locking dApp Tests", () => {
             await provider.connection.confirmTransaction(txHash);
 
             // Fetch the created vesting lock PDA
-            const vestingLockPdaAccount = await program.account.lockPda.fetch(vestingLockPdaKp.publicKey);
+            const vestingLockPdaAccount = await program.account.vestingLock.fetch(vestingLockPdaKp.publicKey);
 
             console.log("Vesting Lock PDA data:", vestingLockPdaAccount);
 
             // Check if the lock amount is correct
-            if (!vestingLockPdaAccount.lockAmount.eq(lockAmount)) {
+            if (!vestingLockPdaAccount.header.lockAmount.eq(lockAmount)) {
                 throw new Error("Vesting lock amount does not match");
             }
         } catch (error) {
@@ -189,104 +339,2524 @@ describe("Solana Token Locking dApp Tests", () => {
         }
     });
 
-    it("should extend the lock time", async () => {
-        const newLockTime = lockTime + 3600; // Extend by 1 hour
+    it("should reject a vesting lock with mismatched user lists", async () => {
+        const metadataPda = await deriveMetadataPDA(projectTokenMint);
+        const authoritySplAta = await anchor.utils.token.associatedAddress({
+            mint: projectTokenMint,
+            owner: provider.wallet.publicKey
+        });
+        const userList = [provider.wallet.publicKey];
 
         try {
-            const txHash = await program.methods
-                .extendLockTime(new BN(newLockTime))
+            await program.methods
+                .lockVesting(
+                    input,
+                    new BN(Math.floor(Date.now() / 1000) + 7200),
+                    lockName,
+                    extraData,
+                    {
+                        tgeBps,
+                        vestingPeriod: new BN(vestingPeriod),
+                        cycleBps,
+                        vestingType: { tranche: {} },
+                        vestingEndTime: new BN(0),
+                    },
+                    userList,
+                    [lockAmount, lockAmount]
+                )
                 .accounts({
-                    lockPda: lockPdaKp.publicKey,
+                    lockPda: vestingLockPdaKp.publicKey,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(provider.wallet.publicKey, projectTokenMint),
+                    splMint: projectTokenMint,
+                    splMintMetadataPda: metadataPda,
                     authority: provider.wallet.publicKey,
-                    systemProgram: web3.SystemProgram.programId,
+                    authoritySplAta: authoritySplAta,
+                    treasury: provider.wallet.publicKey, // defaults to the admin
+                    treasurySplAta: authoritySplAta,
+                    feeExemption: null,
                 })
+                .remainingAccounts(userList.map((user) => ({
+                    pubkey: deriveBeneficiaryPDA(vestingLockPdaKp.publicKey, user),
+                    isWritable: true,
+                    isSigner: false,
+                })))
                 .rpc();
+            throw new Error("Expected an error to be thrown");
+        } catch (error) {
+            if (!error.message.includes("BeneficiaryLengthMismatch")) {
+                console.error("Unexpected error:", error);
+                throw error;
+            }
+        }
+    });
 
-            console.log(`Extend Lock Time Transaction: ${txHash}`);
+    it("should create a vesting pool in batches", async () => {
+        const metadataPda = await deriveMetadataPDA(projectTokenMint);
+        const poolInput = "pool_seed";
+        const [poolPda] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from(poolInput), projectTokenMint.toBuffer(), provider.wallet.publicKey.toBuffer()],
+            program.programId
+        );
+        const poolSplAta = await anchor.utils.token.associatedAddress({
+            mint: projectTokenMint,
+            owner: poolPda
+        });
+        const authoritySplAta = await anchor.utils.token.associatedAddress({
+            mint: projectTokenMint,
+            owner: provider.wallet.publicKey
+        });
 
-            // Confirm transaction
-            await provider.connection.confirmTransaction(txHash);
+        try {
+            await program.methods
+                .createVestingPool(
+                    poolInput,
+                    new BN(Math.floor(Date.now() / 1000) + 7200),
+                    lockName,
+                    extraData,
+                    {
+                        tgeBps,
+                        vestingPeriod: new BN(vestingPeriod),
+                        cycleBps,
+                        vestingType: { tranche: {} },
+                        vestingEndTime: new BN(0),
+                    }
+                )
+                .accounts({
+                    lockPda: poolPda,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(provider.wallet.publicKey, projectTokenMint),
+                    splMint: projectTokenMint,
+                    splMintMetadataPda: metadataPda,
+                    authority: provider.wallet.publicKey,
+                    lockPdaSplAta: poolSplAta,
+                    treasury: provider.wallet.publicKey, // defaults to the admin
+                    feeExemption: null,
+                })
+                .rpc();
 
-            // Fetch the updated lock PDA
-            const lockPdaAccount = await program.account.lockPda.fetch(lockPdaKp.publicKey);
+            // Two batches of one beneficiary each
+            const batches = [[web3.Keypair.generate().publicKey], [web3.Keypair.generate().publicKey]];
+            for (const batch of batches) {
+                await program.methods
+                    .addBeneficiaries(batch, batch.map(() => lockAmount))
+                    .accounts({
+                        lockPda: poolPda,
+                        config: configPda,
+                        mintStats: deriveMintStatsPDA(projectTokenMint),
+                        splMint: projectTokenMint,
+                        authority: provider.wallet.publicKey,
+                        lockPdaSplAta: poolSplAta,
+                        authoritySplAta: authoritySplAta,
+                        treasury: provider.wallet.publicKey,
+                        treasurySplAta: authoritySplAta,
+                        feeExemption: null,
+                    })
+                    .remainingAccounts(batch.map((user) => ({
+                        pubkey: deriveBeneficiaryPDA(poolPda, user),
+                        isWritable: true,
+                        isSigner: false,
+                    })))
+                    .rpc();
+            }
 
-            // Check if the lock time has been extended
-            if (!lockPdaAccount.endTime.eq(new BN(newLockTime))) {
-                throw new Error("Lock time was not extended correctly");
+            await program.methods
+                .finalizeVestingPool()
+                .accounts({ lockPda: poolPda, config: configPda, authority: provider.wallet.publicKey })
+                .rpc();
+
+            const poolAccount = await program.account.vestingLock.fetch(poolPda);
+            if (poolAccount.beneficiaryCount !== 2 || !poolAccount.finalized) {
+                throw new Error("Vesting pool was not built correctly");
+            }
+            if (!poolAccount.header.lockAmount.eq(lockAmount.muln(2))) {
+                throw new Error("Vesting pool amount does not match");
             }
         } catch (error) {
-            console.error("Error in extend lock time test:", error);
+            console.error("Error in vesting pool test:", error);
             throw error;
         }
     });
 
-    it("should handle error when trying to unlock before time", async () => {
-        // Derive metadata PDA
+    it("should reject a merkle claim with an invalid proof", async () => {
         const metadataPda = await deriveMetadataPDA(projectTokenMint);
+        const poolInput = "merkle_seed";
+        const [poolPda] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from(poolInput), projectTokenMint.toBuffer(), provider.wallet.publicKey.toBuffer()],
+            program.programId
+        );
+        const poolSplAta = await anchor.utils.token.associatedAddress({
+            mint: projectTokenMint,
+            owner: poolPda
+        });
+        const authoritySplAta = await anchor.utils.token.associatedAddress({
+            mint: projectTokenMint,
+            owner: provider.wallet.publicKey
+        });
+
+        await program.methods
+            .createVestingPool(
+                poolInput,
+                new BN(Math.floor(Date.now() / 1000) + 7200),
+                lockName,
+                extraData,
+                {
+                    tgeBps,
+                    vestingPeriod: new BN(vestingPeriod),
+                    cycleBps,
+                    vestingType: { tranche: {} },
+                    vestingEndTime: new BN(0),
+                }
+            )
+            .accounts({
+                lockPda: poolPda,
+                config: configPda,
+                lockIds: await nextLockIds(),
+                lockIndex: await deriveLockIndex(provider.wallet.publicKey, projectTokenMint),
+                splMint: projectTokenMint,
+                splMintMetadataPda: metadataPda,
+                authority: provider.wallet.publicKey,
+                lockPdaSplAta: poolSplAta,
+                treasury: provider.wallet.publicKey, // defaults to the admin
+                feeExemption: null,
+            })
+            .rpc();
+
+        // Root of a tree that does not contain the wallet
+        const merkleRoot = Array.from(web3.Keypair.generate().publicKey.toBytes());
+        await program.methods
+            .setMerkleRoot(merkleRoot, lockAmount)
+            .accounts({
+                lockPda: poolPda,
+                config: configPda,
+                mintStats: deriveMintStatsPDA(projectTokenMint),
+                splMint: projectTokenMint,
+                authority: provider.wallet.publicKey,
+                lockPdaSplAta: poolSplAta,
+                authoritySplAta: authoritySplAta,
+                treasury: provider.wallet.publicKey,
+                treasurySplAta: authoritySplAta,
+                feeExemption: null,
+            })
+            .rpc();
+
+        await program.methods
+            .finalizeVestingPool()
+            .accounts({ lockPda: poolPda, config: configPda, authority: provider.wallet.publicKey })
+            .rpc();
 
         try {
-            const txHash = await program.methods
-                .unlockToken(input)
+            await program.methods
+                .unlockMerkleVesting(poolInput, lockAmount, [])
                 .accounts({
-                    lockPda: lockPdaKp.publicKey,
-                    authority: provider.wallet.publicKey,
+                    lockPda: poolPda,
+                    beneficiary: deriveBeneficiaryPDA(poolPda, provider.wallet.publicKey),
+                    config: configPda,
+                    mintStats: deriveMintStatsPDA(projectTokenMint),
                     splMint: projectTokenMint,
                     splMintMetadataPda: metadataPda,
-                    lockPdaSplAta: lockPdaKp.publicKey,
-                    authoritySplAta: provider.wallet.publicKey,
+                    authority: provider.wallet.publicKey,
+                    lockPdaSplAta: poolSplAta,
+                    authoritySplAta: authoritySplAta,
                 })
                 .rpc();
-
-            // Confirm transaction
-            await provider.connection.confirmTransaction(txHash);
             throw new Error("Expected an error to be thrown");
         } catch (error) {
-            if (!error.message.includes("NotUnlockTime")) {
+            if (!error.message.includes("InvalidMerkleProof")) {
                 console.error("Unexpected error:", error);
                 throw error;
             }
         }
     });
 
-    // Vesting unlock test (requires simulating time passing)
-    it("should verify vesting unlock", async () => {
-        // Derive metadata PDA
-        const metadataPda = await deriveMetadataPDA(projectTokenMint);
+    // Helper function to compute an anchor account discriminator
+    const accountDiscriminator = (name: string) =>
+        createHash("sha256").update(`account:${name}`).digest().subarray(0, 8);
 
-        // Simulate time passing for vesting unlock
-        const unlockTime = Math.floor(Date.now() / 1000) + 3600; // 1 hour from now
+    // Helper function to create an account owned by tests/programs/clmm_stub, holding `data`
+    const writeStubAccount = (stubProgramId: web3.PublicKey, account: web3.PublicKey, data: Buffer) =>
+        new web3.TransactionInstruction({
+            programId: stubProgramId,
+            keys: [
+                { pubkey: provider.wallet.publicKey, isSigner: true, isWritable: true },
+                { pubkey: account, isSigner: true, isWritable: true },
+                { pubkey: web3.SystemProgram.programId, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([Buffer.from([0]), data]),
+        });
 
+    const expectError = async (promise: Promise<unknown>, code: string) => {
         try {
-            const txHash = await program.methods
-                .unlockVesting(input)
+            await promise;
+            throw new Error("Expected an error to be thrown");
+        } catch (error) {
+            if (!error.message.includes(code)) {
+                throw error;
+            }
+        }
+    };
+
+    const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+    // Helper function to create a legacy mint with `amount` tokens in the wallet's ATA
+    const createTestMint = async (amount: BN) => {
+        const wallet = provider.wallet.publicKey;
+        const mintKp = web3.Keypair.generate();
+        const walletAta = getAssociatedTokenAddressSync(mintKp.publicKey, wallet);
+
+        const tx = new web3.Transaction().add(
+            web3.SystemProgram.createAccount({
+                fromPubkey: wallet,
+                newAccountPubkey: mintKp.publicKey,
+                space: MINT_SIZE,
+                lamports: await provider.connection.getMinimumBalanceForRentExemption(MINT_SIZE),
+                programId: TOKEN_PROGRAM_ID,
+            }),
+            createInitializeMintInstruction(mintKp.publicKey, 6, wallet, null),
+            createAssociatedTokenAccountInstruction(wallet, walletAta, wallet, mintKp.publicKey),
+            createMintToInstruction(mintKp.publicKey, walletAta, wallet, BigInt(amount.toString()))
+        );
+        await provider.sendAndConfirm(tx, [mintKp]);
+        return mintKp.publicKey;
+    };
+
+    // Helper function to create a token-2022 mint with `extensions`, initialized by `extensionIxs`,
+    // and `amount` tokens in the wallet's ATA. `ataIxs` run between creating the ATA and minting.
+    const createTestMint2022 = async (
+        amount: BN,
+        extensions: ExtensionType[],
+        extensionIxs: (mint: web3.PublicKey) => web3.TransactionInstruction[],
+        freezeAuthority: web3.PublicKey | null = null,
+        ataIxs: (mint: web3.PublicKey, ata: web3.PublicKey) => web3.TransactionInstruction[] = () => []
+    ) => {
+        const wallet = provider.wallet.publicKey;
+        const mintKp = web3.Keypair.generate();
+        const mintLen = getMintLen(extensions);
+        const walletAta = getAssociatedTokenAddressSync(mintKp.publicKey, wallet, false, TOKEN_2022_PROGRAM_ID);
+
+        const tx = new web3.Transaction().add(
+            web3.SystemProgram.createAccount({
+                fromPubkey: wallet,
+                newAccountPubkey: mintKp.publicKey,
+                space: mintLen,
+                lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+                programId: TOKEN_2022_PROGRAM_ID,
+            }),
+            ...extensionIxs(mintKp.publicKey),
+            createInitializeMintInstruction(mintKp.publicKey, 6, wallet, freezeAuthority, TOKEN_2022_PROGRAM_ID),
+            createAssociatedTokenAccountInstruction(wallet, walletAta, wallet, mintKp.publicKey, TOKEN_2022_PROGRAM_ID),
+            ...ataIxs(mintKp.publicKey, walletAta),
+            createMintToInstruction(mintKp.publicKey, walletAta, wallet, BigInt(amount.toString()), [], TOKEN_2022_PROGRAM_ID)
+        );
+        await provider.sendAndConfirm(tx, [mintKp]);
+        return mintKp.publicKey;
+    };
+
+    // Helper function to read the events a transaction emitted
+    const txEvents = async (signature: string) => {
+        await provider.connection.confirmTransaction(signature, "confirmed");
+        const tx = await provider.connection.getTransaction(signature, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+        const parser = new anchor.EventParser(program.programId, program.coder);
+        return Array.from(parser.parseLogs(tx.meta.logMessages));
+    };
+
+    // Helper function to derive the token lock of the wallet at `seed`
+    const deriveTokenLockPDA = (seed: string, mint: web3.PublicKey) => {
+        const [lockPda] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from(seed), mint.toBuffer(), provider.wallet.publicKey.toBuffer()],
+            program.programId
+        );
+        return lockPda;
+    };
+
+    // Helper function to lock `amount` of a mint until `endTime`, owned and paid by the wallet
+    const lockTestToken = async (mint: web3.PublicKey, seed: string, amount: BN, endTime: number, tokenProgram = TOKEN_PROGRAM_ID) => {
+        const wallet = provider.wallet.publicKey;
+        const lockPda = deriveTokenLockPDA(seed, mint);
+        const walletAta = getAssociatedTokenAddressSync(mint, wallet, false, tokenProgram);
+
+        await program.methods
+            .lockToken(seed, amount, new BN(endTime), lockName, extraData, isNft, mint, wsolMint)
+            .accounts({
+                lockPda,
+                config: configPda,
+                lockIds: await nextLockIds(),
+                lockIndex: await deriveLockIndex(wallet, mint),
+                splMint: mint,
+                splMintMetadataPda: web3.SystemProgram.programId, // no metadata
+                authority: wallet,
+                owner: wallet,
+                lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true, tokenProgram),
+                authoritySplAta: walletAta,
+                pool: null,
+                position: null,
+                treasury: wallet, // defaults to the admin
+                treasurySplAta: walletAta,
+                feeExemption: null,
+                tokenProgram,
+            })
+            .rpc();
+        return lockPda;
+    };
+
+    // Helper function to unlock `amount` from a token lock of the wallet
+    const unlockTestToken = (mint: web3.PublicKey, seed: string, amount: BN, tokenProgram = TOKEN_PROGRAM_ID) => {
+        const lockPda = deriveTokenLockPDA(seed, mint);
+        return program.methods
+            .unlockToken(seed, amount)
+            .accounts({
+                lockPda,
+                mintStats: deriveMintStatsPDA(mint),
+                config: configPda,
+                splMint: mint,
+                splMintMetadataPda: web3.SystemProgram.programId,
+                authority: provider.wallet.publicKey,
+                lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true, tokenProgram),
+                authoritySplAta: getAssociatedTokenAddressSync(mint, provider.wallet.publicKey, false, tokenProgram),
+                tokenProgram,
+            })
+            .rpc();
+    };
+
+    // Helper function to close an empty token lock of the wallet, `vaultMint` harvests withheld transfer fees
+    const closeTestLock = async (
+        mint: web3.PublicKey,
+        lockPda: web3.PublicKey,
+        tokenProgram = TOKEN_PROGRAM_ID,
+        vaultMint: web3.PublicKey | null = null
+    ) => {
+        const wallet = provider.wallet.publicKey;
+        const lock = await program.account.tokenLock.fetch(lockPda);
+        return program.methods
+            .closeLock()
+            .accounts({
+                tokenLock: lockPda,
+                lpLock: null,
+                vestingLock: null,
+                config: configPda,
+                lockIdLookup: deriveLockIdPDA(lock.header.lockId),
+                lockIndex: {
+                    ownerIndex: await findIndexPage("owner_index", lock.header.authority, lockPda),
+                    mintIndex: await findIndexPage("mint_index", mint, lockPda),
+                },
+                lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true, tokenProgram),
+                vaultMint,
+                authority: wallet,
+                payer: wallet,
+                tokenProgram,
+            })
+            .rpc();
+    };
+
+    // Helper function to lock a vesting pool of the wallet. By default everything is released at `endTime`,
+    // `schedule` sets tranche bps and period in days, or `linearEndTime` for a linear release.
+    const lockTestVesting = async (
+        mint: web3.PublicKey,
+        seed: string,
+        users: web3.PublicKey[],
+        amounts: number[],
+        endTime: number,
+        schedule: { tgeBps?: number; cycleBps?: number; vestingPeriod?: number; linearEndTime?: number } = {}
+    ) => {
+        const wallet = provider.wallet.publicKey;
+        const lockPda = deriveTokenLockPDA(seed, mint);
+        const walletAta = getAssociatedTokenAddressSync(mint, wallet);
+
+        await program.methods
+            .lockVesting(
+                seed,
+                new BN(endTime),
+                lockName,
+                extraData,
+                {
+                    tgeBps: schedule.tgeBps ?? 10000,
+                    vestingPeriod: new BN(schedule.vestingPeriod ?? 1),
+                    cycleBps: schedule.cycleBps ?? 10000,
+                    vestingType: schedule.linearEndTime ? { linear: {} } : { tranche: {} },
+                    vestingEndTime: new BN(schedule.linearEndTime ?? 0),
+                },
+                users,
+                amounts.map((amount) => new BN(amount))
+            )
+            .accounts({
+                lockPda,
+                config: configPda,
+                lockIds: await nextLockIds(),
+                lockIndex: await deriveLockIndex(wallet, mint),
+                splMint: mint,
+                splMintMetadataPda: web3.SystemProgram.programId, // no metadata
+                authority: wallet,
+                lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true),
+                authoritySplAta: walletAta,
+                treasury: wallet, // defaults to the admin
+                treasurySplAta: walletAta,
+                feeExemption: null,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts(users.map((user) => ({ pubkey: deriveBeneficiaryPDA(lockPda, user), isWritable: true, isSigner: false })))
+            .rpc();
+        return lockPda;
+    };
+
+    // Helper function to claim the vested tokens of `user` from a vesting lock of the wallet
+    const unlockTestVesting = (mint: web3.PublicKey, seed: string, user: web3.Keypair | null = null) => {
+        const lockPda = deriveTokenLockPDA(seed, mint);
+        const authority = user ? user.publicKey : provider.wallet.publicKey;
+        return program.methods
+            .unlockVesting(seed)
+            .accounts({
+                lockPda,
+                mintStats: deriveMintStatsPDA(mint),
+                beneficiary: deriveBeneficiaryPDA(lockPda, authority),
+                config: configPda,
+                splMint: mint,
+                splMintMetadataPda: web3.SystemProgram.programId,
+                authority,
+                lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true),
+                authoritySplAta: getAssociatedTokenAddressSync(mint, authority),
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers(user ? [user] : [])
+            .rpc();
+    };
+
+    // Helper function to close an empty vesting lock of the wallet with the beneficiaries of `users`
+    const closeTestVesting = async (mint: web3.PublicKey, lockPda: web3.PublicKey, users: web3.PublicKey[]) => {
+        const wallet = provider.wallet.publicKey;
+        const lock = await program.account.vestingLock.fetch(lockPda);
+        return program.methods
+            .closeLock()
+            .accounts({
+                tokenLock: null,
+                lpLock: null,
+                vestingLock: lockPda,
+                config: configPda,
+                lockIdLookup: deriveLockIdPDA(lock.header.lockId),
+                lockIndex: {
+                    ownerIndex: await findIndexPage("owner_index", lock.header.authority, lockPda),
+                    mintIndex: await findIndexPage("mint_index", mint, lockPda),
+                },
+                lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true),
+                vaultMint: null,
+                authority: wallet,
+                payer: wallet,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts(users.map((user) => ({ pubkey: deriveBeneficiaryPDA(lockPda, user), isWritable: true, isSigner: false })))
+            .rpc();
+    };
+
+    // Helper function to find the owner_index or mint_index page of `key` listing `lock`
+    const findIndexPage = async (seed: "owner_index" | "mint_index", key: web3.PublicKey, lock: web3.PublicKey) => {
+        const stats = seed === "owner_index"
+            ? await program.account.ownerLockStats.fetchNullable(deriveOwnerStatsPDA(key))
+            : await program.account.mintLockStats.fetchNullable(deriveMintStatsPDA(key));
+        const lastPage = stats ? stats.lockCount.divn(32).toNumber() : -1;
+
+        for (let page = lastPage; page >= 0; page--) {
+            const [indexPage] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from(seed), key.toBuffer(), new BN(page).toArrayLike(Buffer, "le", 8)],
+                program.programId
+            );
+            const account = await program.account.lockIndexPage.fetchNullable(indexPage);
+            if (account && account.locks.some((listed) => listed.equals(lock))) {
+                return indexPage;
+            }
+        }
+        return null;
+    };
+
+    describe("transfer hook mints", () => {
+        // tests/programs/transfer_hook, loaded at this address by the test validator
+        const transferHookProgramId = new web3.PublicKey("98Z3pLE9zTsENgUN3oSv2PGF5bzUCtcGQXphCGBspQfG");
+        const hookMintKp = web3.Keypair.generate();
+        const hookInput = "hook_seed";
+        let validationPda: web3.PublicKey;
+
+        before(async () => {
+            const wallet = provider.wallet.publicKey;
+            const mintLen = getMintLen([ExtensionType.TransferHook]);
+            [validationPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from("extra-account-metas"), hookMintKp.publicKey.toBuffer()],
+                transferHookProgramId
+            );
+            const walletAta = getAssociatedTokenAddressSync(hookMintKp.publicKey, wallet, false, TOKEN_2022_PROGRAM_ID);
+
+            // InitializeExtraAccountMetaList with no extra accounts
+            const initMetasData = Buffer.concat([
+                createHash("sha256").update("spl-transfer-hook-interface:initialize-extra-account-metas").digest().subarray(0, 8),
+                Buffer.alloc(4),
+            ]);
+
+            const tx = new web3.Transaction().add(
+                web3.SystemProgram.createAccount({
+                    fromPubkey: wallet,
+                    newAccountPubkey: hookMintKp.publicKey,
+                    space: mintLen,
+                    lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+                    programId: TOKEN_2022_PROGRAM_ID,
+                }),
+                createInitializeTransferHookInstruction(hookMintKp.publicKey, wallet, transferHookProgramId, TOKEN_2022_PROGRAM_ID),
+                createInitializeMintInstruction(hookMintKp.publicKey, 6, wallet, null, TOKEN_2022_PROGRAM_ID),
+                createAssociatedTokenAccountInstruction(wallet, walletAta, wallet, hookMintKp.publicKey, TOKEN_2022_PROGRAM_ID),
+                createMintToInstruction(hookMintKp.publicKey, walletAta, wallet, lockAmount.muln(2).toNumber(), [], TOKEN_2022_PROGRAM_ID),
+                new web3.TransactionInstruction({
+                    programId: transferHookProgramId,
+                    keys: [
+                        { pubkey: validationPda, isSigner: false, isWritable: true },
+                        { pubkey: hookMintKp.publicKey, isSigner: false, isWritable: false },
+                        { pubkey: wallet, isSigner: true, isWritable: true },
+                        { pubkey: web3.SystemProgram.programId, isSigner: false, isWritable: false },
+                    ],
+                    data: initMetasData,
+                })
+            );
+            await provider.sendAndConfirm(tx, [hookMintKp]);
+        });
+
+        const lockHookMint = async (
+            seed: string,
+            hookAccounts: web3.AccountMeta[],
+            metadata: web3.PublicKey = web3.SystemProgram.programId // no metadata
+        ) => {
+            const wallet = provider.wallet.publicKey;
+            const [hookLockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from(seed), hookMintKp.publicKey.toBuffer(), wallet.toBuffer()],
+                program.programId
+            );
+            const walletAta = getAssociatedTokenAddressSync(hookMintKp.publicKey, wallet, false, TOKEN_2022_PROGRAM_ID);
+
+            return program.methods
+                .lockToken(seed, lockAmount, new BN(lockTime), lockName, extraData, isNft, hookMintKp.publicKey, wsolMint)
                 .accounts({
-                    lockPda: vestingLockPdaKp.publicKey,
+                    lockPda: hookLockPda,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(wallet, hookMintKp.publicKey),
+                    splMint: hookMintKp.publicKey,
+                    splMintMetadataPda: metadata,
+                    authority: wallet,
+                    owner: wallet,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(hookMintKp.publicKey, hookLockPda, true, TOKEN_2022_PROGRAM_ID),
+                    authoritySplAta: walletAta,
+                    pool: null,
+                    position: null,
+                    treasury: wallet, // defaults to the admin
+                    treasurySplAta: walletAta,
+                    feeExemption: null,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                })
+                .remainingAccounts(hookAccounts)
+                .rpc();
+        };
+
+        it("should lock a transfer hook mint with the hook accounts", async () => {
+            await lockHookMint(hookInput, [
+                { pubkey: validationPda, isSigner: false, isWritable: false },
+                { pubkey: transferHookProgramId, isSigner: false, isWritable: false },
+            ]);
+
+            const [hookLockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from(hookInput), hookMintKp.publicKey.toBuffer(), provider.wallet.publicKey.toBuffer()],
+                program.programId
+            );
+            const hookLock = await program.account.tokenLock.fetch(hookLockPda);
+            if (!hookLock.header.lockAmount.eq(lockAmount)) {
+                throw new Error("Transfer hook lock amount does not match");
+            }
+
+            // the mint index lists the lock and counts its tokens
+            const mintStats = await program.account.mintLockStats.fetch(deriveMintStatsPDA(hookMintKp.publicKey));
+            if (!mintStats.lockedAmount.eq(lockAmount) || !mintStats.lockCount.eqn(1)) {
+                throw new Error("Mint stats do not match the lock");
+            }
+            const [mintIndex] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from("mint_index"), hookMintKp.publicKey.toBuffer(), new BN(0).toArrayLike(Buffer, "le", 8)],
+                program.programId
+            );
+            const mintPage = await program.account.lockIndexPage.fetch(mintIndex);
+            if (!mintPage.locks.some((lock) => lock.equals(hookLockPda))) {
+                throw new Error("Mint index does not list the lock");
+            }
+        });
+
+        it("should reject a metadata account that does not belong to the mint", async () => {
+            const hookAccounts = [
+                { pubkey: validationPda, isSigner: false, isWritable: false },
+                { pubkey: transferHookProgramId, isSigner: false, isWritable: false },
+            ];
+            try {
+                await lockHookMint("hook_seed_metadata", hookAccounts, await deriveMetadataPDA(projectTokenMint));
+                throw new Error("Expected an error to be thrown");
+            } catch (error) {
+                if (!error.message.includes("NotValidToken")) {
+                    throw error;
+                }
+            }
+        });
+
+        it("should reject a transfer hook mint without the hook accounts", async () => {
+            try {
+                await lockHookMint("hook_seed_missing", []);
+                throw new Error("Expected an error to be thrown");
+            } catch (error) {
+                if (error.message === "Expected an error to be thrown") {
+                    throw error;
+                }
+            }
+        });
+    });
+
+    describe("clmm position locks", () => {
+        // tests/programs/clmm_stub, loaded at the orca whirlpool address by the test validator
+        const whirlpoolProgramId = new web3.PublicKey("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+        const positionMintKp = web3.Keypair.generate();
+        const positionKp = web3.Keypair.generate();
+        const whirlpoolKp = web3.Keypair.generate();
+        const tokenMintA = web3.Keypair.generate().publicKey;
+        const tokenMintB = web3.Keypair.generate().publicKey;
+        const positionInput = "position_seed";
+        let positionLockPda: web3.PublicKey;
+
+        before(async () => {
+            const wallet = provider.wallet.publicKey;
+            const walletAta = getAssociatedTokenAddressSync(positionMintKp.publicKey, wallet);
+
+            const positionData = Buffer.alloc(216);
+            accountDiscriminator("Position").copy(positionData, 0);
+            whirlpoolKp.publicKey.toBuffer().copy(positionData, 8);
+            positionMintKp.publicKey.toBuffer().copy(positionData, 40);
+
+            const whirlpoolData = Buffer.alloc(653);
+            accountDiscriminator("Whirlpool").copy(whirlpoolData, 0);
+            tokenMintA.toBuffer().copy(whirlpoolData, 101);
+            tokenMintB.toBuffer().copy(whirlpoolData, 181);
+
+            const tx = new web3.Transaction().add(
+                web3.SystemProgram.createAccount({
+                    fromPubkey: wallet,
+                    newAccountPubkey: positionMintKp.publicKey,
+                    space: MINT_SIZE,
+                    lamports: await provider.connection.getMinimumBalanceForRentExemption(MINT_SIZE),
+                    programId: TOKEN_PROGRAM_ID,
+                }),
+                createInitializeMintInstruction(positionMintKp.publicKey, 0, wallet, null),
+                createAssociatedTokenAccountInstruction(wallet, walletAta, wallet, positionMintKp.publicKey),
+                createMintToInstruction(positionMintKp.publicKey, walletAta, wallet, 1),
+                writeStubAccount(whirlpoolProgramId, positionKp.publicKey, positionData),
+                writeStubAccount(whirlpoolProgramId, whirlpoolKp.publicKey, whirlpoolData)
+            );
+            await provider.sendAndConfirm(tx, [positionMintKp, positionKp, whirlpoolKp]);
+
+            [positionLockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from(positionInput), positionMintKp.publicKey.toBuffer(), wallet.toBuffer()],
+                program.programId
+            );
+        });
+
+        const lockPosition = async (seed: string, pool: web3.PublicKey) => {
+            const wallet = provider.wallet.publicKey;
+            const [lockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from(seed), positionMintKp.publicKey.toBuffer(), wallet.toBuffer()],
+                program.programId
+            );
+
+            return program.methods
+                .lockDexPosition(seed, new BN(lockTime), lockName, extraData)
+                .accounts({
+                    lockPda,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(wallet, tokenMintA),
+                    positionMint: positionMintKp.publicKey,
+                    splMintMetadataPda: web3.SystemProgram.programId,
+                    authority: wallet,
+                    owner: wallet,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(positionMintKp.publicKey, lockPda, true),
+                    authoritySplAta: getAssociatedTokenAddressSync(positionMintKp.publicKey, wallet),
+                    dexProgram: whirlpoolProgramId,
+                    position: positionKp.publicKey,
+                    pool,
+                    treasury: wallet, // defaults to the admin
+                    feeExemption: null,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+        };
+
+        // collect_fees accounts: whirlpool, position_authority, position, position_token_account, ...
+        const collectAccounts = (positionAuthority: web3.PublicKey): web3.AccountMeta[] => [
+            { pubkey: whirlpoolKp.publicKey, isSigner: false, isWritable: false },
+            { pubkey: positionAuthority, isSigner: false, isWritable: false },
+            { pubkey: positionKp.publicKey, isSigner: false, isWritable: true },
+            { pubkey: getAssociatedTokenAddressSync(positionMintKp.publicKey, positionLockPda, true), isSigner: false, isWritable: false },
+        ];
+
+        it("should reject a position with a mismatched pool", async () => {
+            try {
+                await lockPosition("position_seed_pool", positionKp.publicKey);
+                throw new Error("Expected an error to be thrown");
+            } catch (error) {
+                if (!error.message.includes("InvalidPoolAccount")) {
+                    throw error;
+                }
+            }
+        });
+
+        it("should lock a whirlpool position", async () => {
+            await lockPosition(positionInput, whirlpoolKp.publicKey);
+
+            const positionLock = await program.account.lpLock.fetch(positionLockPda);
+            if (!positionLock.dexProgram.equals(whirlpoolProgramId) || !positionLock.pool.equals(whirlpoolKp.publicKey)) {
+                throw new Error("Position lock does not record the dex position");
+            }
+            if (!positionLock.tokenMintA.equals(tokenMintA) || !positionLock.tokenMintB.equals(tokenMintB)) {
+                throw new Error("Position lock mints do not match the pool");
+            }
+
+            const lookup = await program.account.lockIdLookup.fetch(deriveLockIdPDA(positionLock.header.lockId));
+            if (!lookup.lock.equals(positionLockPda)) {
+                throw new Error("Lock id lookup does not point at the lock");
+            }
+            const counter = await program.account.lockCounter.fetch(lockCounterPda);
+            if (!counter.lastLockId.eq(positionLock.header.lockId)) {
+                throw new Error("Lock id was not taken from the counter");
+            }
+
+            // lp locks are listed under the pool mint without counting towards its locked amount
+            const mintStats = await program.account.mintLockStats.fetch(deriveMintStatsPDA(tokenMintA));
+            if (!mintStats.lockedAmount.isZero() || !mintStats.lockCount.eqn(1)) {
+                throw new Error("Position lock was counted as locked tokens");
+            }
+        });
+
+        const collectFees = (remainingAccounts: web3.AccountMeta[]) =>
+            program.methods
+                .collectPositionFees(positionInput, null)
+                .accounts({
+                    lockPda: positionLockPda,
+                    config: configPda,
                     authority: provider.wallet.publicKey,
-                    splMint: projectTokenMint,
-                    splMintMetadataPda: metadataPda,
-                    lockPdaSplAta: vestingLockPdaKp.publicKey,
-                    authoritySplAta: provider.wallet.publicKey,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(positionMintKp.publicKey, positionLockPda, true),
+                    dexProgram: whirlpoolProgramId,
                 })
+                .remainingAccounts(remainingAccounts)
                 .rpc();
 
-            console.log(`Vesting Unlock Transaction: ${txHash}`);
+        it("should collect position fees as the lock", async () => {
+            await collectFees(collectAccounts(positionLockPda));
+        });
 
-            // Confirm transaction
-            await provider.connection.confirmTransaction(txHash);
+        it("should reject a collect with another position authority", async () => {
+            try {
+                await collectFees(collectAccounts(provider.wallet.publicKey));
+                throw new Error("Expected an error to be thrown");
+            } catch (error) {
+                if (!error.message.includes("InvalidPositionAccount")) {
+                    throw error;
+                }
+            }
+        });
+    });
 
-            // Fetch the updated vesting lock PDA
-            const vestingLockPdaAccount = await program.account.lockPda.fetch(vestingLockPdaKp.publicKey);
+    describe("raydium clmm position locks", () => {
+        // tests/programs/clmm_stub, also loaded at the raydium clmm address by the test validator
+        const raydiumClmmProgramId = new web3.PublicKey("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+        const positionMintKp = web3.Keypair.generate();
+        const personalPositionKp = web3.Keypair.generate();
+        const poolStateKp = web3.Keypair.generate();
+        const tokenMint0 = web3.Keypair.generate().publicKey;
+        const tokenMint1 = web3.Keypair.generate().publicKey;
+        const positionInput = "raydium_position_seed";
+        let positionLockPda: web3.PublicKey;
 
-            console.log("Vesting Lock PDA data:", vestingLockPdaAccount);
+        before(async () => {
+            const wallet = provider.wallet.publicKey;
+            const walletAta = getAssociatedTokenAddressSync(positionMintKp.publicKey, wallet);
 
-            // Check if the unlock amount is correct
-            const expectedUnlockAmount = Math.floor((vestingLockPdaAccount.lockAmount.toNumber() * firstRelease) / 100);
-            if (!vestingLockPdaAccount.lockAmount.eq(new BN(expectedUnlockAmount))) {
-                throw new Error("Vesting unlock amount does not match");
+            // PersonalPositionState: discriminator, bump, nft_mint, pool_id
+            const positionData = Buffer.alloc(281);
+            accountDiscriminator("PersonalPositionState").copy(positionData, 0);
+            positionMintKp.publicKey.toBuffer().copy(positionData, 9);
+            poolStateKp.publicKey.toBuffer().copy(positionData, 41);
+
+            // PoolState: token_mint_0 at 73, token_mint_1 at 105
+            const poolData = Buffer.alloc(1544);
+            accountDiscriminator("PoolState").copy(poolData, 0);
+            tokenMint0.toBuffer().copy(poolData, 73);
+            tokenMint1.toBuffer().copy(poolData, 105);
+
+            const tx = new web3.Transaction().add(
+                web3.SystemProgram.createAccount({
+                    fromPubkey: wallet,
+                    newAccountPubkey: positionMintKp.publicKey,
+                    space: MINT_SIZE,
+                    lamports: await provider.connection.getMinimumBalanceForRentExemption(MINT_SIZE),
+                    programId: TOKEN_PROGRAM_ID,
+                }),
+                createInitializeMintInstruction(positionMintKp.publicKey, 0, wallet, null),
+                createAssociatedTokenAccountInstruction(wallet, walletAta, wallet, positionMintKp.publicKey),
+                createMintToInstruction(positionMintKp.publicKey, walletAta, wallet, 1),
+                writeStubAccount(raydiumClmmProgramId, personalPositionKp.publicKey, positionData),
+                writeStubAccount(raydiumClmmProgramId, poolStateKp.publicKey, poolData)
+            );
+            await provider.sendAndConfirm(tx, [positionMintKp, personalPositionKp, poolStateKp]);
+
+            [positionLockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from(positionInput), positionMintKp.publicKey.toBuffer(), wallet.toBuffer()],
+                program.programId
+            );
+        });
+
+        // decrease_liquidity_v2 accounts: nft_owner, nft_account, personal_position, pool_state, ...
+        const collectAccounts = (nftOwner: web3.PublicKey): web3.AccountMeta[] => [
+            { pubkey: nftOwner, isSigner: false, isWritable: false },
+            { pubkey: getAssociatedTokenAddressSync(positionMintKp.publicKey, positionLockPda, true), isSigner: false, isWritable: false },
+            { pubkey: personalPositionKp.publicKey, isSigner: false, isWritable: true },
+            { pubkey: poolStateKp.publicKey, isSigner: false, isWritable: true },
+        ];
+
+        const collectFees = (remainingAccounts: web3.AccountMeta[]) =>
+            program.methods
+                .collectPositionFees(positionInput, null)
+                .accounts({
+                    lockPda: positionLockPda,
+                    config: configPda,
+                    authority: provider.wallet.publicKey,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(positionMintKp.publicKey, positionLockPda, true),
+                    dexProgram: raydiumClmmProgramId,
+                })
+                .remainingAccounts(remainingAccounts)
+                .rpc();
+
+        it("should lock a raydium clmm position", async () => {
+            const wallet = provider.wallet.publicKey;
+            await program.methods
+                .lockDexPosition(positionInput, new BN(lockTime), lockName, extraData)
+                .accounts({
+                    lockPda: positionLockPda,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(wallet, tokenMint0),
+                    positionMint: positionMintKp.publicKey,
+                    splMintMetadataPda: web3.SystemProgram.programId,
+                    authority: wallet,
+                    owner: wallet,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(positionMintKp.publicKey, positionLockPda, true),
+                    authoritySplAta: getAssociatedTokenAddressSync(positionMintKp.publicKey, wallet),
+                    dexProgram: raydiumClmmProgramId,
+                    position: personalPositionKp.publicKey,
+                    pool: poolStateKp.publicKey,
+                    treasury: wallet, // defaults to the admin
+                    feeExemption: null,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            const positionLock = await program.account.lpLock.fetch(positionLockPda);
+            if (positionLock.dexName !== "raydium clmm" || !positionLock.dexProgram.equals(raydiumClmmProgramId)) {
+                throw new Error("Position lock does not record the dex");
             }
-        } catch (error) {
-            console.error("Error in vesting unlock test:", error);
-            throw error;
-        }
+            if (!positionLock.position.equals(personalPositionKp.publicKey) || !positionLock.pool.equals(poolStateKp.publicKey)) {
+                throw new Error("Position lock does not record the dex position");
+            }
+            if (!positionLock.tokenMintA.equals(tokenMint0) || !positionLock.tokenMintB.equals(tokenMint1)) {
+                throw new Error("Position lock mints do not match the pool");
+            }
+        });
+
+        it("should collect raydium position fees as the lock", async () => {
+            await collectFees(collectAccounts(positionLockPda));
+        });
+
+        it("should reject a raydium collect with another nft owner", async () => {
+            await expectError(collectFees(collectAccounts(provider.wallet.publicKey)), "InvalidPositionAccount");
+        });
+    });
+
+    describe("verified lp locks", () => {
+        // tests/programs/clmm_stub, also loaded at the raydium cpmm address by the test validator
+        const cpmmProgramId = new web3.PublicKey("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+        const lpMintKp = web3.Keypair.generate();
+        const poolKp = web3.Keypair.generate();
+        const otherPoolKp = web3.Keypair.generate();
+        const projectMint = web3.Keypair.generate().publicKey;
+
+        const poolState = (lpMint: web3.PublicKey) => {
+            const data = Buffer.alloc(637);
+            accountDiscriminator("PoolState").copy(data, 0);
+            lpMint.toBuffer().copy(data, 136);
+            wsolMint.toBuffer().copy(data, 168);
+            projectMint.toBuffer().copy(data, 200);
+            return data;
+        };
+
+        before(async () => {
+            const wallet = provider.wallet.publicKey;
+            const walletAta = getAssociatedTokenAddressSync(lpMintKp.publicKey, wallet);
+
+            const tx = new web3.Transaction().add(
+                web3.SystemProgram.createAccount({
+                    fromPubkey: wallet,
+                    newAccountPubkey: lpMintKp.publicKey,
+                    space: MINT_SIZE,
+                    lamports: await provider.connection.getMinimumBalanceForRentExemption(MINT_SIZE),
+                    programId: TOKEN_PROGRAM_ID,
+                }),
+                createInitializeMintInstruction(lpMintKp.publicKey, 9, wallet, null),
+                createAssociatedTokenAccountInstruction(wallet, walletAta, wallet, lpMintKp.publicKey),
+                createMintToInstruction(lpMintKp.publicKey, walletAta, wallet, lockAmount.toNumber()),
+                writeStubAccount(cpmmProgramId, poolKp.publicKey, poolState(lpMintKp.publicKey)),
+                writeStubAccount(cpmmProgramId, otherPoolKp.publicKey, poolState(web3.Keypair.generate().publicKey))
+            );
+            await provider.sendAndConfirm(tx, [lpMintKp, poolKp, otherPoolKp]);
+        });
+
+        const lockLp = async (seed: string, pool: web3.PublicKey) => {
+            const wallet = provider.wallet.publicKey;
+            const [lockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from(seed), lpMintKp.publicKey.toBuffer(), wallet.toBuffer()],
+                program.programId
+            );
+
+            return program.methods
+                .lockToken(seed, new BN(0), new BN(lockTime), lockName, extraData, true, projectMint, wsolMint)
+                .accounts({
+                    lockPda,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(wallet, projectMint),
+                    splMint: lpMintKp.publicKey,
+                    splMintMetadataPda: web3.SystemProgram.programId,
+                    authority: wallet,
+                    owner: wallet,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(lpMintKp.publicKey, lockPda, true),
+                    authoritySplAta: getAssociatedTokenAddressSync(lpMintKp.publicKey, wallet),
+                    pool,
+                    position: null,
+                    treasury: wallet, // defaults to the admin
+                    treasurySplAta: getAssociatedTokenAddressSync(lpMintKp.publicKey, wallet),
+                    feeExemption: null,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+        };
+
+        it("should reject a pool of another lp mint", async () => {
+            try {
+                await lockLp("verified_lp_other", otherPoolKp.publicKey);
+                throw new Error("Expected an error to be thrown");
+            } catch (error) {
+                if (!error.message.includes("LpMintMismatch")) {
+                    throw error;
+                }
+            }
+        });
+
+        it("should verify an lp mint against its pool", async () => {
+            await lockLp("verified_lp", poolKp.publicKey);
+
+            const [lockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from("verified_lp"), lpMintKp.publicKey.toBuffer(), provider.wallet.publicKey.toBuffer()],
+                program.programId
+            );
+            const lpLock = await program.account.lpLock.fetch(lockPda);
+            if (!lpLock.verified || !lpLock.pool.equals(poolKp.publicKey) || lpLock.dexName !== "raydium cpmm") {
+                throw new Error("LP lock was not verified against the pool");
+            }
+        });
+
+        const unlockLp = (mint: web3.PublicKey, delegate?: web3.Keypair) => {
+            const owner = provider.wallet.publicKey;
+            const [lockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from("verified_lp"), lpMintKp.publicKey.toBuffer(), owner.toBuffer()],
+                program.programId
+            );
+
+            return program.methods
+                .unlockLp("verified_lp")
+                .accounts({
+                    lockPda,
+                    config: configPda,
+                    authority: delegate ? delegate.publicKey : owner,
+                    owner,
+                    positionMint: mint,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true),
+                    ownerSplAta: getAssociatedTokenAddressSync(mint, owner),
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers(delegate ? [delegate] : [])
+                .rpc();
+        };
+
+        it("should reject an lp unlock from a foreign wallet", async () => {
+            await expectError(unlockLp(lpMintKp.publicKey, web3.Keypair.generate()), "AuthorizationErr");
+        });
+
+        it("should reject an lp unlock with a mismatched mint", async () => {
+            await expectError(unlockLp(wsolMint), "SplMintError");
+        });
+
+        it("should let an approved delegate unlock an lp lock", async () => {
+            const delegate = web3.Keypair.generate();
+            const [lockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from("verified_lp"), lpMintKp.publicKey.toBuffer(), provider.wallet.publicKey.toBuffer()],
+                program.programId
+            );
+
+            await expectError(unlockLp(lpMintKp.publicKey, delegate), "AuthorizationErr");
+
+            await program.methods
+                .setLpDelegate(delegate.publicKey)
+                .accounts({ lpLock: lockPda, config: configPda, authority: provider.wallet.publicKey })
+                .rpc();
+
+            // the delegate passes the authority check and reaches the lock time check
+            await expectError(unlockLp(lpMintKp.publicKey, delegate), "NotUnlockTime");
+        });
+    });
+
+    describe("token-2022 lp locks", () => {
+        it("should lock a token-2022 lp mint with create_lock_pda and unlock it", async () => {
+            const wallet = provider.wallet.publicKey;
+            const lpMint = await createTestMint2022(lockAmount, [], () => []);
+            const walletAta = getAssociatedTokenAddressSync(lpMint, wallet, false, TOKEN_2022_PROGRAM_ID);
+
+            // create_lock_pda locks are derived from the spl_mint account, here the first pool mint
+            const [lockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from("lp_2022"), wsolMint.toBuffer(), wallet.toBuffer()],
+                program.programId
+            );
+            const vault = getAssociatedTokenAddressSync(lpMint, lockPda, true, TOKEN_2022_PROGRAM_ID);
+
+            await program.methods
+                .createLockPda("lp_2022", lockAmount, new BN(Math.floor(Date.now() / 1000) + 3), lockName, extraData, "manual", wsolMint, projectTokenMint)
+                .accounts({
+                    lockPda,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(wallet, wsolMint),
+                    authority: wallet,
+                    lockPdaAuthority: wallet,
+                    splMint: wsolMint,
+                    positionMint: lpMint,
+                    splMintMetadataPda: web3.SystemProgram.programId, // no metadata
+                    lockPdaSplAta: vault,
+                    authoritySplAta: walletAta,
+                    pool: null,
+                    position: null,
+                    treasury: wallet, // defaults to the admin
+                    treasurySplAta: walletAta,
+                    feeExemption: null,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                })
+                .rpc();
+
+            const lpLock = await program.account.lpLock.fetch(lockPda);
+            if (!lpLock.header.lockAmount.eq(lockAmount) || !lpLock.header.vaultMint.equals(lpMint)) {
+                throw new Error("LP lock does not hold the token-2022 mint");
+            }
+            const vaultBalance = await provider.connection.getTokenAccountBalance(vault);
+            if (vaultBalance.value.amount !== lockAmount.toString()) {
+                throw new Error("The vault did not receive the lp tokens");
+            }
+
+            await sleep(5000);
+            await program.methods
+                .unlockLp("lp_2022")
+                .accounts({
+                    lockPda,
+                    config: configPda,
+                    authority: wallet,
+                    owner: wallet,
+                    positionMint: lpMint,
+                    lockPdaSplAta: vault,
+                    ownerSplAta: walletAta,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                })
+                .rpc();
+
+            const unlocked = await program.account.lpLock.fetch(lockPda);
+            if (!unlocked.header.lockAmount.isZero() || !unlocked.header.unlockedAmount.eq(lockAmount)) {
+                throw new Error("LP lock amounts do not reflect the unlock");
+            }
+            const walletBalance = await provider.connection.getTokenAccountBalance(walletAta);
+            if (walletBalance.value.amount !== lockAmount.toString()) {
+                throw new Error("The owner did not get the lp tokens back");
+            }
+        });
+    });
+
+    it("should extend the lock time", async () => {
+        const newLockTime = lockTime + 3600; // Extend by 1 hour
+
+        try {
+            const txHash = await program.methods
+                .extendLockTime(new BN(newLockTime))
+                .accounts({
+                    tokenLock: lockPdaKp.publicKey,
+                    lpLock: null,
+                    vestingLock: null,
+                    config: configPda,
+                    authority: provider.wallet.publicKey,
+                })
+                .rpc();
+
+            console.log(`Extend Lock Time Transaction: ${txHash}`);
+
+            // Confirm transaction
+            await provider.connection.confirmTransaction(txHash);
+
+            // Fetch the updated lock PDA
+            const lockPdaAccount = await program.account.tokenLock.fetch(lockPdaKp.publicKey);
+
+            // Check if the lock time has been extended
+            if (!lockPdaAccount.header.endTime.eq(new BN(newLockTime))) {
+                throw new Error("Lock time was not extended correctly");
+            }
+        } catch (error) {
+            console.error("Error in extend lock time test:", error);
+            throw error;
+        }
+    });
+
+    it("should shift a linear vesting schedule when extending", async () => {
+        const metadataPda = await deriveMetadataPDA(projectTokenMint);
+        const poolInput = "extend_vesting_seed";
+        const [poolPda] = web3.PublicKey.findProgramAddressSync(
+            [Buffer.from(poolInput), projectTokenMint.toBuffer(), provider.wallet.publicKey.toBuffer()],
+            program.programId
+        );
+        const cliff = Math.floor(Date.now() / 1000) + 7200;
+        const vestingEnd = cliff + 7200;
+
+        await program.methods
+            .createVestingPool(
+                poolInput,
+                new BN(cliff),
+                lockName,
+                extraData,
+                tgeBps,
+                new BN(0),
+                0,
+                { linear: {} },
+                new BN(vestingEnd)
+            )
+            .accounts({
+                lockPda: poolPda,
+                config: configPda,
+                lockIds: await nextLockIds(),
+                lockIndex: await deriveLockIndex(provider.wallet.publicKey, projectTokenMint),
+                splMint: projectTokenMint,
+                splMintMetadataPda: metadataPda,
+                authority: provider.wallet.publicKey,
+                lockPdaSplAta: await anchor.utils.token.associatedAddress({ mint: projectTokenMint, owner: poolPda }),
+                treasury: provider.wallet.publicKey, // defaults to the admin
+                feeExemption: null,
+            })
+            .rpc();
+
+        const extend = (newEndTime: number) =>
+            program.methods
+                .extendLockTime(new BN(newEndTime))
+                .accounts({
+                    tokenLock: null,
+                    lpLock: null,
+                    vestingLock: poolPda,
+                    config: configPda,
+                    authority: provider.wallet.publicKey,
+                })
+                .rpc();
+
+        await extend(cliff + 3600);
+
+        const poolAccount = await program.account.vestingLock.fetch(poolPda);
+        if (!poolAccount.header.endTime.eq(new BN(cliff + 3600)) || !poolAccount.vestingEndTime.eq(new BN(vestingEnd + 3600))) {
+            throw new Error("Vesting schedule was not shifted with the end time");
+        }
+
+        try {
+            await extend(cliff + 3600);
+            throw new Error("Expected an error to be thrown");
+        } catch (error) {
+            if (!error.message.includes("NotBiggerThanEndTime")) {
+                throw error;
+            }
+        }
+    });
+
+    it("should handle error when trying to unlock before time", async () => {
+        // Derive metadata PDA
+        const metadataPda = await deriveMetadataPDA(projectTokenMint);
+
+        try {
+            const txHash = await program.methods
+                .unlockToken(input, lockAmount)
+                .accounts({
+                    lockPda: lockPdaKp.publicKey,
+                    config: configPda,
+                    mintStats: deriveMintStatsPDA(projectTokenMint),
+                    authority: provider.wallet.publicKey,
+                    splMint: projectTokenMint,
+                    splMintMetadataPda: metadataPda,
+                    lockPdaSplAta: lockPdaKp.publicKey,
+                    authoritySplAta: provider.wallet.publicKey,
+                })
+                .rpc();
+
+            // Confirm transaction
+            await provider.connection.confirmTransaction(txHash);
+            throw new Error("Expected an error to be thrown");
+        } catch (error) {
+            if (!error.message.includes("NotUnlockTime")) {
+                console.error("Unexpected error:", error);
+                throw error;
+            }
+        }
+    });
+
+    // Vesting unlock test (requires simulating time passing)
+    it("should verify vesting unlock", async () => {
+        // Derive metadata PDA
+        const metadataPda = await deriveMetadataPDA(projectTokenMint);
+
+        // Simulate time passing for vesting unlock
+        const unlockTime = Math.floor(Date.now() / 1000) + 3600; // 1 hour from now
+
+        try {
+            const txHash = await program.methods
+                .unlockVesting(input)
+                .accounts({
+                    lockPda: vestingLockPdaKp.publicKey,
+                    beneficiary: deriveBeneficiaryPDA(vestingLockPdaKp.publicKey, provider.wallet.publicKey),
+                    config: configPda,
+                    mintStats: deriveMintStatsPDA(projectTokenMint),
+                    authority: provider.wallet.publicKey,
+                    splMint: projectTokenMint,
+                    splMintMetadataPda: metadataPda,
+                    lockPdaSplAta: vestingLockPdaKp.publicKey,
+                    authoritySplAta: provider.wallet.publicKey,
+                })
+                .rpc();
+
+            console.log(`Vesting Unlock Transaction: ${txHash}`);
+
+            // Confirm transaction
+            await provider.connection.confirmTransaction(txHash);
+
+            // Fetch the updated vesting lock PDA
+            const vestingLockPdaAccount = await program.account.vestingLock.fetch(vestingLockPdaKp.publicKey);
+
+            console.log("Vesting Lock PDA data:", vestingLockPdaAccount);
+
+            const beneficiaryAccount = await program.account.vestingBeneficiary.fetch(
+                deriveBeneficiaryPDA(vestingLockPdaKp.publicKey, provider.wallet.publicKey)
+            );
+
+            // Check if the unlock amount is correct
+            const expectedUnlockAmount = Math.floor((beneficiaryAccount.amount.toNumber() * tgeBps) / 10000);
+            if (!beneficiaryAccount.releasedAmount.eq(new BN(expectedUnlockAmount))) {
+                throw new Error("Vesting unlock amount does not match");
+            }
+        } catch (error) {
+            console.error("Error in vesting unlock test:", error);
+            throw error;
+        }
+    });
+
+    it("should reject locks while the program is paused", async () => {
+        const metadataPda = await deriveMetadataPDA(projectTokenMint);
+        const authoritySplAta = await anchor.utils.token.associatedAddress({
+            mint: projectTokenMint,
+            owner: provider.wallet.publicKey
+        });
+
+        await program.methods
+            .setPaused(true)
+            .accounts({ config: configPda, admin: provider.wallet.publicKey })
+            .rpc();
+
+        try {
+            await program.methods
+                .lockToken(
+                    input,
+                    lockAmount,
+                    new BN(lockTime),
+                    lockName,
+                    extraData,
+                    isNft,
+                    projectTokenMint,
+                    wsolMint
+                )
+                .accounts({
+                    lockPda: lockPdaKp.publicKey,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(provider.wallet.publicKey, projectTokenMint),
+                    splMint: projectTokenMint,
+                    splMintMetadataPda: metadataPda,
+                    authority: provider.wallet.publicKey,
+                    owner: provider.wallet.publicKey,
+                    authoritySplAta: authoritySplAta,
+                    pool: null,
+                    position: null,
+                    treasury: provider.wallet.publicKey, // defaults to the admin
+                    treasurySplAta: authoritySplAta,
+                    feeExemption: null,
+                })
+                .rpc();
+            throw new Error("Expected an error to be thrown");
+        } catch (error) {
+            if (!error.message.includes("ProgramPaused")) {
+                console.error("Unexpected error:", error);
+                throw error;
+            }
+        } finally {
+            await program.methods
+                .setPaused(false)
+                .accounts({ config: configPda, admin: provider.wallet.publicKey })
+                .rpc();
+        }
+    });
+
+    describe("lock indexes", () => {
+        it("should move a lock to the new owner's index on accept", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestToken(mint, "index_accept", lockAmount, lockTime);
+            const newOwner = web3.Keypair.generate();
+
+            // the new owner pays for its index accounts
+            await provider.sendAndConfirm(new web3.Transaction().add(
+                web3.SystemProgram.transfer({ fromPubkey: wallet, toPubkey: newOwner.publicKey, lamports: web3.LAMPORTS_PER_SOL / 10 })
+            ));
+
+            await program.methods
+                .transferLockOwnership(newOwner.publicKey)
+                .accounts({ tokenLock: lockPda, lpLock: null, vestingLock: null, config: configPda, authority: wallet })
+                .rpc();
+
+            const { ownerStats, ownerIndex } = await deriveLockIndex(newOwner.publicKey, mint);
+            await program.methods
+                .acceptLockOwnership()
+                .accounts({
+                    tokenLock: lockPda,
+                    lpLock: null,
+                    vestingLock: null,
+                    config: configPda,
+                    lockIndex: {
+                        previousOwnerIndex: await findIndexPage("owner_index", wallet, lockPda),
+                        ownerStats,
+                        ownerIndex,
+                    },
+                    pendingAuthority: newOwner.publicKey,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([newOwner])
+                .rpc();
+
+            if (await findIndexPage("owner_index", wallet, lockPda)) {
+                throw new Error("Previous owner index still lists the lock");
+            }
+            if (!(await findIndexPage("owner_index", newOwner.publicKey, lockPda))) {
+                throw new Error("New owner index does not list the lock");
+            }
+        });
+
+        it("should take a closed lock off both indexes", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestToken(mint, "index_close", lockAmount, Math.floor(Date.now() / 1000) + 3);
+
+            await sleep(5000);
+            await unlockTestToken(mint, "index_close", lockAmount);
+
+            const mintStats = await program.account.mintLockStats.fetch(deriveMintStatsPDA(mint));
+            if (!mintStats.lockedAmount.eqn(0)) {
+                throw new Error("Mint stats still count the unlocked tokens");
+            }
+
+            await closeTestLock(mint, lockPda);
+
+            if (await findIndexPage("owner_index", wallet, lockPda) || await findIndexPage("mint_index", mint, lockPda)) {
+                throw new Error("Closed lock is still listed");
+            }
+        });
+
+        it("should reject an index page that does not list the lock", async () => {
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestToken(mint, "index_other_page", lockAmount, Math.floor(Date.now() / 1000) + 3);
+            const otherMint = await createTestMint(lockAmount);
+            const otherLockPda = await lockTestToken(otherMint, "index_other_page", lockAmount, lockTime);
+
+            await sleep(5000);
+            await unlockTestToken(mint, "index_other_page", lockAmount);
+
+            const lock = await program.account.tokenLock.fetch(lockPda);
+            await expectError(
+                program.methods
+                    .closeLock()
+                    .accounts({
+                        tokenLock: lockPda,
+                        lpLock: null,
+                        vestingLock: null,
+                        config: configPda,
+                        lockIdLookup: deriveLockIdPDA(lock.header.lockId),
+                        lockIndex: {
+                            ownerIndex: await findIndexPage("owner_index", provider.wallet.publicKey, lockPda),
+                            mintIndex: await findIndexPage("mint_index", otherMint, otherLockPda),
+                        },
+                        lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true),
+                        vaultMint: null,
+                        authority: provider.wallet.publicKey,
+                        payer: provider.wallet.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc(),
+                "InvalidLockIndexAccount"
+            );
+        });
+    });
+
+    describe("closing vesting locks", () => {
+        it("should close the beneficiary accounts with the lock", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const other = web3.Keypair.generate();
+            const users = [wallet, other.publicKey];
+            const amounts = [lockAmount.divn(2).toNumber(), lockAmount.divn(2).toNumber()];
+
+            // the other beneficiary pays for its token account on claim
+            await provider.sendAndConfirm(new web3.Transaction().add(
+                web3.SystemProgram.transfer({ fromPubkey: wallet, toPubkey: other.publicKey, lamports: web3.LAMPORTS_PER_SOL / 10 })
+            ));
+
+            const lockPda = await lockTestVesting(mint, "close_vesting", users, amounts, Math.floor(Date.now() / 1000) + 3);
+
+            await sleep(5000);
+            await unlockTestVesting(mint, "close_vesting");
+            await unlockTestVesting(mint, "close_vesting", other);
+
+            // a missing beneficiary keeps the lock open
+            await closeTestVesting(mint, lockPda, [wallet]);
+            const lock = await program.account.vestingLock.fetch(lockPda);
+            if (lock.beneficiaryCount !== 1) {
+                throw new Error("Beneficiary count does not match");
+            }
+            if (await provider.connection.getAccountInfo(deriveBeneficiaryPDA(lockPda, wallet))) {
+                throw new Error("Beneficiary account was not closed");
+            }
+
+            const balanceBefore = await provider.connection.getBalance(wallet);
+            await closeTestVesting(mint, lockPda, [other.publicKey]);
+
+            if (await provider.connection.getAccountInfo(deriveBeneficiaryPDA(lockPda, other.publicKey))) {
+                throw new Error("Beneficiary account was not closed");
+            }
+            if (await provider.connection.getAccountInfo(lockPda)) {
+                throw new Error("Vesting lock was not closed");
+            }
+            if (await provider.connection.getBalance(wallet) <= balanceBefore) {
+                throw new Error("Rent was not refunded");
+            }
+        });
+
+        it("should reject a beneficiary account of another lock", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestVesting(mint, "close_vesting_other", [wallet], [lockAmount.toNumber()], Math.floor(Date.now() / 1000) + 3);
+            const otherMint = await createTestMint(lockAmount);
+            const otherLockPda = await lockTestVesting(otherMint, "close_vesting_other", [wallet], [lockAmount.toNumber()], lockTime);
+
+            await sleep(5000);
+            await unlockTestVesting(mint, "close_vesting_other");
+
+            const lock = await program.account.vestingLock.fetch(lockPda);
+            await expectError(
+                program.methods
+                    .closeLock()
+                    .accounts({
+                        tokenLock: null,
+                        lpLock: null,
+                        vestingLock: lockPda,
+                        config: configPda,
+                        lockIdLookup: deriveLockIdPDA(lock.header.lockId),
+                        lockIndex: {
+                            ownerIndex: await findIndexPage("owner_index", wallet, lockPda),
+                            mintIndex: await findIndexPage("mint_index", mint, lockPda),
+                        },
+                        lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true),
+                        vaultMint: null,
+                        authority: wallet,
+                        payer: wallet,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .remainingAccounts([{ pubkey: deriveBeneficiaryPDA(otherLockPda, wallet), isWritable: true, isSigner: false }])
+                    .rpc(),
+                "InvalidBeneficiaryAccount"
+            );
+        });
+    });
+
+    describe("lock fees", () => {
+        const treasury = web3.Keypair.generate();
+        const flatFee = new BN(web3.LAMPORTS_PER_SOL / 100);
+        const feeBps = 100; // 1%
+
+        const deriveFeeExemptionPDA = (target: web3.PublicKey) => {
+            const [feeExemption] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from("fee_exemption"), target.toBuffer()],
+                program.programId
+            );
+            return feeExemption;
+        };
+
+        const addExemption = async (target: web3.PublicKey) => {
+            await program.methods
+                .addFeeExemption(target)
+                .accounts({
+                    config: configPda,
+                    feeExemption: deriveFeeExemptionPDA(target),
+                    admin: provider.wallet.publicKey,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .rpc();
+            return deriveFeeExemptionPDA(target);
+        };
+
+        const createTreasuryAta = async (mint: web3.PublicKey) => {
+            const treasuryAta = getAssociatedTokenAddressSync(mint, treasury.publicKey);
+            await provider.sendAndConfirm(new web3.Transaction().add(
+                createAssociatedTokenAccountInstruction(provider.wallet.publicKey, treasuryAta, treasury.publicKey, mint)
+            ));
+            return treasuryAta;
+        };
+
+        // Locks `amount` of `mint`, or its whole balance as a manual lp lock of `projectMint`
+        const lockWithFees = async (
+            mint: web3.PublicKey,
+            seed: string,
+            amount: BN,
+            accounts: { treasury?: web3.PublicKey; treasurySplAta: web3.PublicKey | null; feeExemption?: web3.PublicKey },
+            projectMint: web3.PublicKey | null = null
+        ) => {
+            const wallet = provider.wallet.publicKey;
+            const lockPda = deriveTokenLockPDA(seed, mint);
+
+            await program.methods
+                .lockToken(seed, projectMint ? new BN(0) : amount, new BN(lockTime), lockName, extraData, projectMint !== null, projectMint ?? mint, wsolMint)
+                .accounts({
+                    lockPda,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(wallet, projectMint ?? mint),
+                    splMint: mint,
+                    splMintMetadataPda: web3.SystemProgram.programId, // no metadata
+                    authority: wallet,
+                    owner: wallet,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true),
+                    authoritySplAta: getAssociatedTokenAddressSync(mint, wallet),
+                    pool: null,
+                    position: null,
+                    treasury: accounts.treasury ?? treasury.publicKey,
+                    treasurySplAta: accounts.treasurySplAta,
+                    feeExemption: accounts.feeExemption ?? null,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+            return lockPda;
+        };
+
+        const tokenBalance = async (account: web3.PublicKey) =>
+            new BN((await provider.connection.getTokenAccountBalance(account)).value.amount);
+
+        before(async () => {
+            await program.methods
+                .updateFeeConfig(treasury.publicKey, flatFee, feeBps)
+                .accounts({ config: configPda, admin: provider.wallet.publicKey })
+                .rpc();
+        });
+
+        after(async () => {
+            await program.methods
+                .updateFeeConfig(provider.wallet.publicKey, new BN(0), 0)
+                .accounts({ config: configPda, admin: provider.wallet.publicKey })
+                .rpc();
+        });
+
+        it("should charge the flat fee and the token fee on top of a token lock", async () => {
+            const mint = await createTestMint(lockAmount.muln(2));
+            const treasuryAta = await createTreasuryAta(mint);
+            const lamportsBefore = await provider.connection.getBalance(treasury.publicKey);
+
+            const lockPda = await lockWithFees(mint, "fee_token", lockAmount, { treasurySplAta: treasuryAta });
+
+            const lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.lockAmount.eq(lockAmount)) {
+                throw new Error("Lock amount does not match");
+            }
+            if (!(await tokenBalance(treasuryAta)).eq(lockAmount.muln(feeBps).divn(10000))) {
+                throw new Error("Token fee does not match");
+            }
+            if (await provider.connection.getBalance(treasury.publicKey) - lamportsBefore !== flatFee.toNumber()) {
+                throw new Error("Flat fee does not match");
+            }
+        });
+
+        it("should take the token fee out of a manual lp lock", async () => {
+            const lpMint = await createTestMint(lockAmount);
+            const projectMint = await createTestMint(lockAmount);
+            const treasuryAta = await createTreasuryAta(lpMint);
+
+            // an exempt project token passed as an argument is not verified and does not waive the fee
+            const feeExemption = await addExemption(projectMint);
+            const lockPda = await lockWithFees(lpMint, "fee_lp", new BN(0), { treasurySplAta: treasuryAta, feeExemption }, projectMint);
+
+            const fee = lockAmount.muln(feeBps).divn(10000);
+            const lock = await program.account.lpLock.fetch(lockPda);
+            if (!lock.header.lockAmount.eq(lockAmount.sub(fee))) {
+                throw new Error("Lock amount does not match");
+            }
+            if (!(await tokenBalance(treasuryAta)).eq(fee)) {
+                throw new Error("Token fee does not match");
+            }
+        });
+
+        it("should take the token fee out of a create_lock_pda lp lock", async () => {
+            const wallet = provider.wallet.publicKey;
+            const lpMint = await createTestMint(lockAmount);
+            const treasuryAta = await createTreasuryAta(lpMint);
+            const [lockPda] = web3.PublicKey.findProgramAddressSync(
+                [Buffer.from("fee_lock_pda"), wsolMint.toBuffer(), wallet.toBuffer()],
+                program.programId
+            );
+
+            // the whole balance is enough, the fee is not charged on top
+            await program.methods
+                .createLockPda("fee_lock_pda", lockAmount, new BN(lockTime), lockName, extraData, "manual", wsolMint, projectTokenMint)
+                .accounts({
+                    lockPda,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(wallet, wsolMint),
+                    authority: wallet,
+                    lockPdaAuthority: wallet,
+                    splMint: wsolMint,
+                    positionMint: lpMint,
+                    splMintMetadataPda: web3.SystemProgram.programId, // no metadata
+                    lockPdaSplAta: getAssociatedTokenAddressSync(lpMint, lockPda, true),
+                    authoritySplAta: getAssociatedTokenAddressSync(lpMint, wallet),
+                    pool: null,
+                    position: null,
+                    treasury: treasury.publicKey,
+                    treasurySplAta: treasuryAta,
+                    feeExemption: null,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            const fee = lockAmount.muln(feeBps).divn(10000);
+            const lock = await program.account.lpLock.fetch(lockPda);
+            if (!lock.header.lockAmount.eq(lockAmount.sub(fee))) {
+                throw new Error("Lock amount does not match");
+            }
+            if (!(await tokenBalance(treasuryAta)).eq(fee)) {
+                throw new Error("Token fee does not match");
+            }
+        });
+
+        it("should not charge an exempt mint", async () => {
+            const mint = await createTestMint(lockAmount);
+            const feeExemption = await addExemption(mint);
+            const lamportsBefore = await provider.connection.getBalance(treasury.publicKey);
+
+            // no fee is due, so the treasury token account can be left out
+            const lockPda = await lockWithFees(mint, "fee_exempt", lockAmount, { treasurySplAta: null, feeExemption });
+
+            const lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.lockAmount.eq(lockAmount)) {
+                throw new Error("Lock amount does not match");
+            }
+            if (await provider.connection.getBalance(treasury.publicKey) !== lamportsBefore) {
+                throw new Error("Exempt lock paid the flat fee");
+            }
+        });
+
+        it("should require the treasury token account when a token fee is due", async () => {
+            const mint = await createTestMint(lockAmount.muln(2));
+            await expectError(
+                lockWithFees(mint, "fee_no_ata", lockAmount, { treasurySplAta: null }),
+                "MissingTreasuryTokenAccount"
+            );
+        });
+
+        it("should reject a treasury other than the config treasury", async () => {
+            const mint = await createTestMint(lockAmount.muln(2));
+            await expectError(
+                lockWithFees(mint, "fee_wrong_treasury", lockAmount, {
+                    treasury: provider.wallet.publicKey,
+                    treasurySplAta: getAssociatedTokenAddressSync(mint, provider.wallet.publicKey),
+                }),
+                "InvalidTreasury"
+            );
+        });
+
+        it("should reject a treasury token account of another owner", async () => {
+            const mint = await createTestMint(lockAmount.muln(2));
+            await expectError(
+                lockWithFees(mint, "fee_wrong_ata", lockAmount, {
+                    treasurySplAta: getAssociatedTokenAddressSync(mint, provider.wallet.publicKey),
+                }),
+                "ConstraintTokenOwner"
+            );
+        });
+    });
+
+    describe("transfer fee mints", () => {
+        const transferFeeBps = 100; // 1%
+
+        const createTransferFeeMint = () =>
+            createTestMint2022(lockAmount, [ExtensionType.TransferFeeConfig], (mint) => [
+                createInitializeTransferFeeConfigInstruction(
+                    mint,
+                    provider.wallet.publicKey,
+                    provider.wallet.publicKey,
+                    transferFeeBps,
+                    BigInt(lockAmount.toString()),
+                    TOKEN_2022_PROGRAM_ID
+                ),
+            ]);
+
+        it("should lock and unlock what arrives after the transfer fee", async () => {
+            const mint = await createTransferFeeMint();
+            const lockPda = await lockTestToken(mint, "transfer_fee", lockAmount, Math.floor(Date.now() / 1000) + 3, TOKEN_2022_PROGRAM_ID);
+
+            const lockFee = lockAmount.muln(transferFeeBps).divn(10000);
+            const lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.lockAmount.eq(lockAmount.sub(lockFee))) {
+                throw new Error("Lock amount does not match");
+            }
+
+            const [lockSignature] = await provider.connection.getSignaturesForAddress(lockPda, { limit: 1 }, "confirmed");
+            const createEvent = (await txEvents(lockSignature.signature)).find((event) => event.name === "CreateLockEvent");
+            if (!createEvent || !createEvent.data.transferFee.eq(lockFee)) {
+                throw new Error("Create event transfer fee does not match");
+            }
+
+            await sleep(5000);
+            const received = lockAmount.sub(lockFee);
+            const unlockFee = received.muln(transferFeeBps).divn(10000);
+            const unlockSignature = await unlockTestToken(mint, "transfer_fee", received, TOKEN_2022_PROGRAM_ID);
+
+            const unlocked = await program.account.tokenLock.fetch(lockPda);
+            if (!unlocked.header.lockAmount.eqn(0) || !unlocked.header.unlockedAmount.eq(received)) {
+                throw new Error("Unlocked amount does not match");
+            }
+
+            const unlockEvent = (await txEvents(unlockSignature)).find((event) => event.name === "UnlockEvent");
+            if (!unlockEvent || !unlockEvent.data.transferFee.eq(unlockFee)) {
+                throw new Error("Unlock event transfer fee does not match");
+            }
+        });
+
+        it("should harvest withheld transfer fees when closing the lock", async () => {
+            const mint = await createTransferFeeMint();
+            const lockPda = await lockTestToken(mint, "transfer_fee_close", lockAmount, Math.floor(Date.now() / 1000) + 3, TOKEN_2022_PROGRAM_ID);
+            const lock = await program.account.tokenLock.fetch(lockPda);
+
+            await sleep(5000);
+            await unlockTestToken(mint, "transfer_fee_close", lock.header.lockAmount, TOKEN_2022_PROGRAM_ID);
+
+            // the deposit fee is still withheld in the empty vault
+            await expectError(closeTestLock(mint, lockPda, TOKEN_2022_PROGRAM_ID), "WithheldTransferFees");
+
+            await closeTestLock(mint, lockPda, TOKEN_2022_PROGRAM_ID, mint);
+            if (await provider.connection.getAccountInfo(lockPda)) {
+                throw new Error("Lock was not closed");
+            }
+            if (await provider.connection.getAccountInfo(getAssociatedTokenAddressSync(mint, lockPda, true, TOKEN_2022_PROGRAM_ID))) {
+                throw new Error("Vault was not closed");
+            }
+        });
+    });
+
+    describe("metadata pointer mints", () => {
+        const createPointerMint = (metadataAddress: (mint: web3.PublicKey) => web3.PublicKey) =>
+            createTestMint2022(lockAmount, [ExtensionType.MetadataPointer], (mint) => [
+                createInitializeMetadataPointerInstruction(mint, provider.wallet.publicKey, metadataAddress(mint), TOKEN_2022_PROGRAM_ID),
+            ]);
+
+        const lockWithMetadata = async (mint: web3.PublicKey, seed: string, metadata: web3.PublicKey) => {
+            const wallet = provider.wallet.publicKey;
+            const lockPda = deriveTokenLockPDA(seed, mint);
+            const walletAta = getAssociatedTokenAddressSync(mint, wallet, false, TOKEN_2022_PROGRAM_ID);
+
+            await program.methods
+                .lockToken(seed, lockAmount, new BN(lockTime), lockName, extraData, isNft, mint, wsolMint)
+                .accounts({
+                    lockPda,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(wallet, mint),
+                    splMint: mint,
+                    splMintMetadataPda: metadata,
+                    authority: wallet,
+                    owner: wallet,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true, TOKEN_2022_PROGRAM_ID),
+                    authoritySplAta: walletAta,
+                    pool: null,
+                    position: null,
+                    treasury: wallet, // defaults to the admin
+                    treasurySplAta: null,
+                    feeExemption: null,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                })
+                .rpc();
+            return lockPda;
+        };
+
+        it("should accept metadata stored in the mint", async () => {
+            const mint = await createPointerMint((mint) => mint);
+            const lockPda = await lockWithMetadata(mint, "pointer_self", mint);
+
+            const lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.splMintMetadataPda.equals(mint)) {
+                throw new Error("Lock does not record the mint metadata");
+            }
+        });
+
+        it("should reject a pointer to an account of another program", async () => {
+            // any program could write a fake name there, here the clmm stub at the whirlpool address
+            const whirlpoolProgramId = new web3.PublicKey("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+            const fakeMetadataKp = web3.Keypair.generate();
+            await provider.sendAndConfirm(
+                new web3.Transaction().add(writeStubAccount(whirlpoolProgramId, fakeMetadataKp.publicKey, Buffer.alloc(128))),
+                [fakeMetadataKp]
+            );
+
+            const mint = await createPointerMint(() => fakeMetadataKp.publicKey);
+            await expectError(lockWithMetadata(mint, "pointer_other_owner", fakeMetadataKp.publicKey), "NotValidToken");
+        });
+    });
+
+    describe("token-2022 extension policy", () => {
+        it("should flag a permanent delegate on the lock", async () => {
+            const delegate = web3.Keypair.generate().publicKey;
+            const mint = await createTestMint2022(lockAmount, [ExtensionType.PermanentDelegate], (mint) => [
+                createInitializePermanentDelegateInstruction(mint, delegate, TOKEN_2022_PROGRAM_ID),
+            ]);
+            const lockPda = await lockTestToken(mint, "permanent_delegate", lockAmount, lockTime, TOKEN_2022_PROGRAM_ID);
+
+            const lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.permanentDelegate) {
+                throw new Error("Lock does not flag the permanent delegate");
+            }
+        });
+
+        it("should not flag a mint without a permanent delegate", async () => {
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestToken(mint, "no_permanent_delegate", lockAmount, lockTime);
+
+            const lock = await program.account.tokenLock.fetch(lockPda);
+            if (lock.header.permanentDelegate) {
+                throw new Error("Lock flags a permanent delegate");
+            }
+        });
+
+        it("should accept an initialized default account state", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint2022(lockAmount, [ExtensionType.DefaultAccountState], (mint) => [
+                createInitializeDefaultAccountStateInstruction(mint, AccountState.Initialized, TOKEN_2022_PROGRAM_ID),
+            ], wallet);
+            await lockTestToken(mint, "default_state_initialized", lockAmount, lockTime, TOKEN_2022_PROGRAM_ID);
+        });
+
+        it("should reject a frozen default account state", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint2022(lockAmount, [ExtensionType.DefaultAccountState], (mint) => [
+                createInitializeDefaultAccountStateInstruction(mint, AccountState.Frozen, TOKEN_2022_PROGRAM_ID),
+            ], wallet, (mint, ata) => [
+                createThawAccountInstruction(ata, mint, wallet, [], TOKEN_2022_PROGRAM_ID),
+            ]);
+            await expectError(
+                lockTestToken(mint, "default_state_frozen", lockAmount, lockTime, TOKEN_2022_PROGRAM_ID),
+                "UnsafeMintExtension"
+            );
+        });
+
+        it("should reject a non-transferable mint", async () => {
+            const mint = await createTestMint2022(lockAmount, [ExtensionType.NonTransferable], (mint) => [
+                createInitializeNonTransferableMintInstruction(mint, TOKEN_2022_PROGRAM_ID),
+            ]);
+            await expectError(
+                lockTestToken(mint, "non_transferable", lockAmount, lockTime, TOKEN_2022_PROGRAM_ID),
+                "UnsafeMintExtension"
+            );
+        });
+    });
+
+    describe("per-beneficiary vesting claims", () => {
+        it("should let every beneficiary claim its own tranche", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const other = web3.Keypair.generate();
+            const amount = lockAmount.divn(2);
+
+            // the other beneficiary pays for its token account on claim
+            await provider.sendAndConfirm(new web3.Transaction().add(
+                web3.SystemProgram.transfer({ fromPubkey: wallet, toPubkey: other.publicKey, lamports: web3.LAMPORTS_PER_SOL / 10 })
+            ));
+
+            // half at the end time, the other half one day later
+            const lockPda = await lockTestVesting(
+                mint,
+                "per_user_release",
+                [wallet, other.publicKey],
+                [amount.toNumber(), amount.toNumber()],
+                Math.floor(Date.now() / 1000) + 3,
+                { tgeBps: 5000, cycleBps: 5000, vestingPeriod: 1 }
+            );
+
+            await sleep(5000);
+            await unlockTestVesting(mint, "per_user_release");
+
+            // the first claim does not block the other beneficiary
+            await unlockTestVesting(mint, "per_user_release", other);
+
+            for (const user of [wallet, other.publicKey]) {
+                const beneficiary = await program.account.vestingBeneficiary.fetch(deriveBeneficiaryPDA(lockPda, user));
+                if (!beneficiary.releasedAmount.eq(amount.divn(2))) {
+                    throw new Error("Released amount does not match the tge release");
+                }
+            }
+
+            const lock = await program.account.vestingLock.fetch(lockPda);
+            if (!lock.header.lockAmount.eq(amount) || !lock.header.unlockedAmount.eq(amount)) {
+                throw new Error("Lock amounts do not match both claims");
+            }
+
+            // the same beneficiary can't claim the tge release twice
+            await expectError(unlockTestVesting(mint, "per_user_release"), "AlreadyDidFirstClaim");
+        });
+    });
+
+    describe("lock ownership transfer", () => {
+        const extendLockTime = (lockPda: web3.PublicKey, endTime: number, authority: web3.Keypair | null = null) =>
+            program.methods
+                .extendLockTime(new BN(endTime))
+                .accounts({
+                    tokenLock: lockPda,
+                    lpLock: null,
+                    vestingLock: null,
+                    config: configPda,
+                    authority: authority ? authority.publicKey : provider.wallet.publicKey,
+                })
+                .signers(authority ? [authority] : [])
+                .rpc();
+
+        const acceptOwnership = async (lockPda: web3.PublicKey, mint: web3.PublicKey, newOwner: web3.Keypair) => {
+            const { ownerStats, ownerIndex } = await deriveLockIndex(newOwner.publicKey, mint);
+            return program.methods
+                .acceptLockOwnership()
+                .accounts({
+                    tokenLock: lockPda,
+                    lpLock: null,
+                    vestingLock: null,
+                    config: configPda,
+                    lockIndex: {
+                        previousOwnerIndex: await findIndexPage("owner_index", provider.wallet.publicKey, lockPda),
+                        ownerStats,
+                        ownerIndex,
+                    },
+                    pendingAuthority: newOwner.publicKey,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([newOwner])
+                .rpc();
+        };
+
+        it("should hand a lock over in two steps", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestToken(mint, "ownership", lockAmount, lockTime);
+            const newOwner = web3.Keypair.generate();
+            const stranger = web3.Keypair.generate();
+
+            // both pay for index accounts or transaction fees
+            await provider.sendAndConfirm(new web3.Transaction().add(
+                web3.SystemProgram.transfer({ fromPubkey: wallet, toPubkey: newOwner.publicKey, lamports: web3.LAMPORTS_PER_SOL / 10 }),
+                web3.SystemProgram.transfer({ fromPubkey: wallet, toPubkey: stranger.publicKey, lamports: web3.LAMPORTS_PER_SOL / 10 })
+            ));
+
+            const signature = await program.methods
+                .transferLockOwnership(newOwner.publicKey)
+                .accounts({ tokenLock: lockPda, lpLock: null, vestingLock: null, config: configPda, authority: wallet })
+                .rpc();
+
+            // indexers see the pending transfer
+            const proposed = (await txEvents(signature)).find((event) => event.name === "ProposeLockOwnershipEvent");
+            if (!proposed || !proposed.data.lockPda.equals(lockPda) || !proposed.data.pendingAuthority.equals(newOwner.publicKey)) {
+                throw new Error("Proposing a new owner did not emit the pending owner");
+            }
+
+            // proposing does not hand over the lock yet
+            let lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.authority.equals(wallet) || !lock.header.pendingAuthority.equals(newOwner.publicKey)) {
+                throw new Error("Proposed owner does not match");
+            }
+
+            await expectError(acceptOwnership(lockPda, mint, stranger), "AuthorizationErr");
+            await acceptOwnership(lockPda, mint, newOwner);
+
+            lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.authority.equals(newOwner.publicKey) || !lock.header.pendingAuthority.equals(web3.PublicKey.default)) {
+                throw new Error("New owner does not match");
+            }
+            if (!lock.header.creator.equals(wallet) || !lockPda.equals(deriveTokenLockPDA("ownership", mint))) {
+                throw new Error("Lock address no longer derives from the creator");
+            }
+
+            // only the new owner manages the lock
+            await expectError(extendLockTime(lockPda, lockTime + 60), "AuthorizationErr");
+            await extendLockTime(lockPda, lockTime + 60, newOwner);
+
+            lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.endTime.eqn(lockTime + 60)) {
+                throw new Error("New owner could not extend the lock");
+            }
+        });
+
+        it("should only let the owner propose a new owner", async () => {
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestToken(mint, "ownership_stranger", lockAmount, lockTime);
+            const stranger = web3.Keypair.generate();
+
+            await provider.sendAndConfirm(new web3.Transaction().add(
+                web3.SystemProgram.transfer({ fromPubkey: provider.wallet.publicKey, toPubkey: stranger.publicKey, lamports: web3.LAMPORTS_PER_SOL / 10 })
+            ));
+
+            await expectError(
+                program.methods
+                    .transferLockOwnership(stranger.publicKey)
+                    .accounts({ tokenLock: lockPda, lpLock: null, vestingLock: null, config: configPda, authority: stranger.publicKey })
+                    .signers([stranger])
+                    .rpc(),
+                "AuthorizationErr"
+            );
+        });
+    });
+
+    describe("increasing lock amounts", () => {
+        const increaseLockAmount = (
+            mint: web3.PublicKey,
+            lock: { tokenLock?: web3.PublicKey; vestingLock?: web3.PublicKey },
+            amount: BN
+        ) => {
+            const lockPda = lock.tokenLock ?? lock.vestingLock;
+            const walletAta = getAssociatedTokenAddressSync(mint, provider.wallet.publicKey);
+            return program.methods
+                .increaseLockAmount(amount)
+                .accounts({
+                    tokenLock: lock.tokenLock ?? null,
+                    lpLock: null,
+                    vestingLock: lock.vestingLock ?? null,
+                    mintStats: deriveMintStatsPDA(mint),
+                    config: configPda,
+                    splMint: mint,
+                    authority: provider.wallet.publicKey,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true),
+                    authoritySplAta: walletAta,
+                    treasury: provider.wallet.publicKey, // defaults to the admin
+                    treasurySplAta: walletAta,
+                    feeExemption: null,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+        };
+
+        it("should add tokens to a token lock without moving its end time", async () => {
+            const mint = await createTestMint(lockAmount.muln(2));
+            const lockPda = await lockTestToken(mint, "increase", lockAmount, lockTime);
+            const topUp = lockAmount.divn(4);
+
+            const signature = await increaseLockAmount(mint, { tokenLock: lockPda }, topUp);
+
+            const lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.lockAmount.eq(lockAmount.add(topUp))) {
+                throw new Error("Lock amount does not include the top-up");
+            }
+            if (!lock.header.endTime.eqn(lockTime)) {
+                throw new Error("Top-up moved the end time");
+            }
+
+            const vault = await provider.connection.getTokenAccountBalance(getAssociatedTokenAddressSync(mint, lockPda, true));
+            if (!new BN(vault.value.amount).eq(lockAmount.add(topUp))) {
+                throw new Error("Vault balance does not match the lock amount");
+            }
+
+            const mintStats = await program.account.mintLockStats.fetch(deriveMintStatsPDA(mint));
+            if (!mintStats.lockedAmount.eq(lockAmount.add(topUp))) {
+                throw new Error("Mint stats do not count the top-up");
+            }
+
+            const event = (await txEvents(signature)).find((event) => event.name === "IncreaseLockAmountEvent");
+            if (!event || !event.data.amount.eq(topUp) || !event.data.lockAmount.eq(lockAmount.add(topUp))) {
+                throw new Error("Increase event does not match");
+            }
+        });
+
+        it("should reject a zero top-up", async () => {
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestToken(mint, "increase_zero", lockAmount, lockTime);
+            await expectError(increaseLockAmount(mint, { tokenLock: lockPda }, new BN(0)), "AmountZero");
+        });
+
+        it("should reject a top-up of a vesting lock", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount.muln(2));
+            const lockPda = await lockTestVesting(mint, "increase_vesting", [wallet], [lockAmount.toNumber()], lockTime);
+            await expectError(increaseLockAmount(mint, { vestingLock: lockPda }, lockAmount), "NotTokenLock");
+        });
+    });
+
+    describe("linear vesting", () => {
+        it("should release the tge and then vest per second until the end", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const endTime = Math.floor(Date.now() / 1000) + 3;
+            const linearEndTime = endTime + 20;
+            const tgeBps = 1000; // 10% at the end time
+
+            const lockPda = await lockTestVesting(mint, "linear_vesting", [wallet], [lockAmount.toNumber()], endTime, { tgeBps, linearEndTime });
+            const beneficiaryPda = deriveBeneficiaryPDA(lockPda, wallet);
+
+            await sleep(5000);
+            await unlockTestVesting(mint, "linear_vesting");
+
+            // tge + (amount - tge) * elapsed / duration, rounded down
+            const tgeAmount = lockAmount.muln(tgeBps).divn(10000);
+            let beneficiary = await program.account.vestingBeneficiary.fetch(beneficiaryPda);
+            const elapsed = beneficiary.lastClaimTime.subn(endTime);
+            const expected = tgeAmount.add(lockAmount.sub(tgeAmount).mul(elapsed).divn(linearEndTime - endTime));
+            if (!beneficiary.releasedAmount.eq(expected)) {
+                throw new Error("Linear release does not match the elapsed time");
+            }
+            if (beneficiary.releasedAmount.gte(lockAmount)) {
+                throw new Error("Linear release vested everything before the end");
+            }
+
+            // past vesting_end_time the rest, including rounding dust, is released
+            await sleep((linearEndTime - Math.floor(Date.now() / 1000) + 2) * 1000);
+            await unlockTestVesting(mint, "linear_vesting");
+
+            beneficiary = await program.account.vestingBeneficiary.fetch(beneficiaryPda);
+            const lock = await program.account.vestingLock.fetch(lockPda);
+            if (!beneficiary.releasedAmount.eq(lockAmount) || !lock.header.lockAmount.isZero()) {
+                throw new Error("Linear release did not sweep the remainder");
+            }
+        });
+
+        it("should reject a linear end before the end time", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            await expectError(
+                lockTestVesting(mint, "linear_invalid", [wallet], [lockAmount.toNumber()], lockTime, { linearEndTime: lockTime - 1 }),
+                "InvalidVestingSchedule"
+            );
+        });
+    });
+
+    describe("merkle vesting claims", () => {
+        const tokenBalance = async (account: web3.PublicKey) =>
+            new BN((await provider.connection.getTokenAccountBalance(account)).value.amount);
+
+        // keccak256(beneficiary || amount as little endian u64)
+        const merkleLeaf = (beneficiary: web3.PublicKey, amount: BN) =>
+            Buffer.from(keccak_256(Buffer.concat([beneficiary.toBuffer(), amount.toArrayLike(Buffer, "le", 8)])));
+
+        // pairs are hashed in sorted order
+        const hashPair = (a: Buffer, b: Buffer) =>
+            Buffer.from(keccak_256(Buffer.compare(a, b) <= 0 ? Buffer.concat([a, b]) : Buffer.concat([b, a])));
+
+        // Helper function to create a finalized linear merkle pool of the wallet funded with `totalAmount`
+        const createMerklePool = async (mint: web3.PublicKey, seed: string, root: Buffer, totalAmount: BN, endTime: number, linearEndTime: number) => {
+            const wallet = provider.wallet.publicKey;
+            const lockPda = deriveTokenLockPDA(seed, mint);
+            const walletAta = getAssociatedTokenAddressSync(mint, wallet);
+            const vault = getAssociatedTokenAddressSync(mint, lockPda, true);
+
+            await program.methods
+                .createVestingPool(
+                    seed,
+                    new BN(endTime),
+                    lockName,
+                    extraData,
+                    {
+                        tgeBps: 1000,
+                        vestingPeriod: new BN(0),
+                        cycleBps: 0,
+                        vestingType: { linear: {} },
+                        vestingEndTime: new BN(linearEndTime),
+                    }
+                )
+                .accounts({
+                    lockPda,
+                    config: configPda,
+                    lockIds: await nextLockIds(),
+                    lockIndex: await deriveLockIndex(wallet, mint),
+                    splMint: mint,
+                    splMintMetadataPda: web3.SystemProgram.programId, // no metadata
+                    authority: wallet,
+                    lockPdaSplAta: vault,
+                    treasury: wallet, // defaults to the admin
+                    feeExemption: null,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            await program.methods
+                .setMerkleRoot(Array.from(root), totalAmount)
+                .accounts({
+                    lockPda,
+                    config: configPda,
+                    mintStats: deriveMintStatsPDA(mint),
+                    splMint: mint,
+                    authority: wallet,
+                    lockPdaSplAta: vault,
+                    authoritySplAta: walletAta,
+                    treasury: wallet,
+                    treasurySplAta: walletAta,
+                    feeExemption: null,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            await program.methods
+                .finalizeVestingPool()
+                .accounts({ lockPda, config: configPda, authority: wallet })
+                .rpc();
+            return lockPda;
+        };
+
+        // Helper function to claim `amount` from a merkle pool of the wallet with `proof`
+        const claimMerkle = (mint: web3.PublicKey, seed: string, amount: BN, proof: Buffer[]) => {
+            const wallet = provider.wallet.publicKey;
+            const lockPda = deriveTokenLockPDA(seed, mint);
+            return program.methods
+                .unlockMerkleVesting(seed, amount, proof.map((node) => Array.from(node)))
+                .accounts({
+                    lockPda,
+                    beneficiary: deriveBeneficiaryPDA(lockPda, wallet),
+                    config: configPda,
+                    mintStats: deriveMintStatsPDA(mint),
+                    splMint: mint,
+                    splMintMetadataPda: web3.SystemProgram.programId,
+                    authority: wallet,
+                    lockPdaSplAta: getAssociatedTokenAddressSync(mint, lockPda, true),
+                    authoritySplAta: getAssociatedTokenAddressSync(mint, wallet),
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+        };
+
+        it("should pay a proven claim and then only the newly vested amount", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const walletAta = getAssociatedTokenAddressSync(mint, wallet);
+            const endTime = Math.floor(Date.now() / 1000) + 3;
+            const linearEndTime = endTime + 20;
+
+            // two leaves, the proof of each one is the other leaf
+            const claimAmount = lockAmount.divn(2);
+            const otherLeaf = merkleLeaf(web3.Keypair.generate().publicKey, lockAmount.sub(claimAmount));
+            const root = hashPair(merkleLeaf(wallet, claimAmount), otherLeaf);
+            const lockPda = await createMerklePool(mint, "merkle_claims", root, lockAmount, endTime, linearEndTime);
+            const beneficiaryPda = deriveBeneficiaryPDA(lockPda, wallet);
+
+            await sleep(5000);
+            await claimMerkle(mint, "merkle_claims", claimAmount, [otherLeaf]);
+
+            let beneficiary = await program.account.vestingBeneficiary.fetch(beneficiaryPda);
+            const firstRelease = beneficiary.releasedAmount;
+            if (!beneficiary.amount.eq(claimAmount) || firstRelease.isZero() || firstRelease.gte(claimAmount)) {
+                throw new Error("First claim did not release part of the proven amount");
+            }
+            if (!(await tokenBalance(walletAta)).eq(firstRelease)) {
+                throw new Error("The wallet did not receive the first claim");
+            }
+
+            // the same leaf with another amount is not in the tree
+            await expectError(claimMerkle(mint, "merkle_claims", claimAmount.addn(1), [otherLeaf]), "InvalidMerkleProof");
+
+            await sleep(3000);
+            await claimMerkle(mint, "merkle_claims", claimAmount, [otherLeaf]);
+
+            beneficiary = await program.account.vestingBeneficiary.fetch(beneficiaryPda);
+            const delta = beneficiary.releasedAmount.sub(firstRelease);
+            if (delta.isZero()) {
+                throw new Error("Repeat claim released nothing");
+            }
+            if (!(await tokenBalance(walletAta)).eq(beneficiary.releasedAmount)) {
+                throw new Error("Repeat claim paid more than the newly vested amount");
+            }
+
+            const lock = await program.account.vestingLock.fetch(lockPda);
+            if (!lock.header.lockAmount.eq(lockAmount.sub(beneficiary.releasedAmount)) || lock.beneficiaryCount !== 1) {
+                throw new Error("Pool amounts do not reflect the claims");
+            }
+        });
+
+        it("should reject a claim with an amount other than the proven one", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const endTime = Math.floor(Date.now() / 1000) + 3;
+
+            const claimAmount = lockAmount.divn(2);
+            const otherLeaf = merkleLeaf(web3.Keypair.generate().publicKey, lockAmount.sub(claimAmount));
+            const root = hashPair(merkleLeaf(wallet, claimAmount), otherLeaf);
+            const lockPda = await createMerklePool(mint, "merkle_amount", root, lockAmount, endTime, endTime + 20);
+
+            await sleep(5000);
+            await expectError(claimMerkle(mint, "merkle_amount", lockAmount, [otherLeaf]), "InvalidMerkleProof");
+
+            const lock = await program.account.vestingLock.fetch(lockPda);
+            if (lock.beneficiaryCount !== 0 || !lock.header.lockAmount.eq(lockAmount)) {
+                throw new Error("A rejected claim changed the pool");
+            }
+        });
+    });
+
+    describe("partial withdrawals", () => {
+        const tokenBalance = async (account: web3.PublicKey) =>
+            new BN((await provider.connection.getTokenAccountBalance(account)).value.amount);
+
+        it("should unlock part of a lock and keep the rest locked", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestToken(mint, "partial_unlock", lockAmount, Math.floor(Date.now() / 1000) + 3);
+            const vault = getAssociatedTokenAddressSync(mint, lockPda, true);
+            const walletAta = getAssociatedTokenAddressSync(mint, wallet);
+            const partAmount = lockAmount.divn(4);
+            const restAmount = lockAmount.sub(partAmount);
+
+            await sleep(5000);
+            const signature = await unlockTestToken(mint, "partial_unlock", partAmount);
+
+            let lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.lockAmount.eq(restAmount) || !lock.header.unlockedAmount.eq(partAmount)) {
+                throw new Error("Lock amounts do not reflect the partial unlock");
+            }
+            if (!(await tokenBalance(vault)).eq(restAmount)) {
+                throw new Error("The rest did not stay in the vault");
+            }
+            if (!(await tokenBalance(walletAta)).eq(partAmount)) {
+                throw new Error("The wallet did not receive the partial amount");
+            }
+
+            const unlocked = (await txEvents(signature)).find((event) => event.name === "UnlockEvent");
+            if (!unlocked || !unlocked.data.unlockAmount.eq(partAmount) || !unlocked.data.remainingAmount.eq(restAmount)) {
+                throw new Error("UnlockEvent does not report the partial amount");
+            }
+
+            // more than what is left is rejected and changes nothing
+            await expectError(unlockTestToken(mint, "partial_unlock", restAmount.addn(1)), "LowBalance");
+
+            await unlockTestToken(mint, "partial_unlock", restAmount);
+
+            lock = await program.account.tokenLock.fetch(lockPda);
+            if (!lock.header.lockAmount.isZero() || !lock.header.unlockedAmount.eq(lockAmount)) {
+                throw new Error("Lock amounts do not reflect the full unlock");
+            }
+            if (!(await tokenBalance(vault)).isZero()) {
+                throw new Error("The vault was not emptied");
+            }
+            if (!(await tokenBalance(walletAta)).eq(lockAmount)) {
+                throw new Error("The wallet did not receive the whole lock");
+            }
+            await expectError(unlockTestToken(mint, "partial_unlock", new BN(1)), "AlreadyUnlocked");
+        });
+    });
+
+    describe("closing token locks", () => {
+        it("should refund the rent of the lock, its vault and its lookup to the payer", async () => {
+            const wallet = provider.wallet.publicKey;
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestToken(mint, "close_refund", lockAmount, Math.floor(Date.now() / 1000) + 3);
+            const vault = getAssociatedTokenAddressSync(mint, lockPda, true);
+            const lookup = deriveLockIdPDA((await program.account.tokenLock.fetch(lockPda)).header.lockId);
+
+            await sleep(5000);
+            await unlockTestToken(mint, "close_refund", lockAmount);
+
+            const rent = (await Promise.all([lockPda, vault, lookup].map((account) => provider.connection.getBalance(account))))
+                .reduce((sum, lamports) => sum + lamports, 0);
+            const balanceBefore = await provider.connection.getBalance(wallet);
+
+            const signature = await closeTestLock(mint, lockPda);
+            await provider.connection.confirmTransaction(signature, "confirmed");
+            const tx = await provider.connection.getTransaction(signature, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+
+            for (const account of [lockPda, vault, lookup]) {
+                if (await provider.connection.getAccountInfo(account)) {
+                    throw new Error("Lock account was not closed");
+                }
+            }
+            if (await provider.connection.getBalance(wallet) !== balanceBefore + rent - tx.meta.fee) {
+                throw new Error("Rent was not refunded to the payer");
+            }
+        });
+
+        it("should not close a lock that still holds tokens", async () => {
+            const mint = await createTestMint(lockAmount);
+            const lockPda = await lockTestToken(mint, "close_not_empty", lockAmount, lockTime);
+            await expectError(closeTestLock(mint, lockPda), "LockNotEmpty");
+        });
+    });
+
+    describe("vesting input validation", () => {
+        const wallet = provider.wallet.publicKey;
+
+        it("should reject a duplicate beneficiary", async () => {
+            const mint = await createTestMint(lockAmount);
+            const amount = lockAmount.divn(2).toNumber();
+            await expectError(
+                lockTestVesting(mint, "invalid_duplicate", [wallet, wallet], [amount, amount], lockTime),
+                "DuplicateBeneficiary"
+            );
+        });
+
+        it("should reject a zero beneficiary amount", async () => {
+            const mint = await createTestMint(lockAmount);
+            await expectError(
+                lockTestVesting(mint, "invalid_amount", [wallet], [0], lockTime),
+                "BeneficiaryAmountZero"
+            );
+        });
+
+        it("should reject a tge above 100%", async () => {
+            const mint = await createTestMint(lockAmount);
+            await expectError(
+                lockTestVesting(mint, "invalid_tge", [wallet], [lockAmount.toNumber()], lockTime, { tgeBps: 10001 }),
+                "InvalidTgeBps"
+            );
+        });
+
+        it("should reject a zero cycle release", async () => {
+            const mint = await createTestMint(lockAmount);
+            await expectError(
+                lockTestVesting(mint, "invalid_cycle", [wallet], [lockAmount.toNumber()], lockTime, { tgeBps: 2000, cycleBps: 0 }),
+                "InvalidCycleBps"
+            );
+        });
+
+        it("should reject a zero vesting period", async () => {
+            const mint = await createTestMint(lockAmount);
+            await expectError(
+                lockTestVesting(mint, "invalid_period", [wallet], [lockAmount.toNumber()], lockTime, { vestingPeriod: 0 }),
+                "InvalidVestingPeriod"
+            );
+        });
     });
-});
\ No newline at end of file
+});